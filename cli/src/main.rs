@@ -0,0 +1,384 @@
+//! Command line client to build, run and test projects through xbase daemon.
+use anyhow::{anyhow, bail, Context as _, Result};
+use std::path::PathBuf;
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::task::JoinHandle;
+use xbase_proto::*;
+
+static DAEMON_SOCKET_PATH: &str = "/tmp/xbase.socket";
+static DAEMON_EVENTS_SOCKET_PATH: &str = "/tmp/xbase-events.socket";
+
+static USAGE: &str = "\
+Usage: xbase-cli <COMMAND> [OPTIONS] [-- <ARGS>...]
+
+Commands:
+  build     Build target
+  run       Build and run target
+  test      Build and test target
+  status    Print daemon projects, watchers and running processes
+
+Options:
+  -t, --target <TARGET>                 Target to build, run or test
+  -c, --configuration <CONFIGURATION>   Configuration to build with [default: Debug]
+  -s, --scheme <SCHEME>                 Scheme to build with
+  -d, --device <DEVICE>                 Simulator device name to run or test on
+  -r, --root <ROOT>                     Project root [default: current directory]
+  -w, --watch                           Rebuild or rerun on file changes until interrupted
+  -e, --env <KEY=VALUE>                 Environment variable to run target with
+      --cwd <DIR>                       Working directory to run target in
+  -- <ARGS>...                          Arguments to run target with
+  -h, --help                            Print help information";
+
+/// Command to run
+#[derive(Debug, PartialEq, Eq)]
+enum Cmd {
+    Build,
+    Run,
+    Test,
+    Status,
+}
+
+/// Command line arguments
+#[derive(Debug)]
+struct Args {
+    cmd: Cmd,
+    target: Option<String>,
+    configuration: BuildConfiguration,
+    scheme: Option<String>,
+    device: Option<String>,
+    root: Option<PathBuf>,
+    watch: bool,
+    launch: LaunchOptions,
+}
+
+impl Args {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self> {
+        let cmd = match args.next().as_deref() {
+            Some("build") => Cmd::Build,
+            Some("run") => Cmd::Run,
+            Some("test") => Cmd::Test,
+            Some("status") => Cmd::Status,
+            Some("-h" | "--help") | None => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            Some(cmd) => bail!("Unknown command `{cmd}`"),
+        };
+
+        let mut parsed = Self {
+            cmd,
+            target: None,
+            configuration: BuildConfiguration::Debug,
+            scheme: None,
+            device: None,
+            root: None,
+            watch: false,
+            launch: LaunchOptions::default(),
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow!("Missing value for `{arg}`"))
+            };
+            match arg.as_str() {
+                "-t" | "--target" => parsed.target = Some(value()?),
+                "-c" | "--configuration" => {
+                    let value = value()?;
+                    parsed.configuration = BuildConfiguration::from_str(&value)
+                        .unwrap_or(BuildConfiguration::Custom(value));
+                }
+                "-s" | "--scheme" => parsed.scheme = Some(value()?),
+                "-d" | "--device" => parsed.device = Some(value()?),
+                "-r" | "--root" => parsed.root = Some(value()?.into()),
+                "-w" | "--watch" => parsed.watch = true,
+                "-e" | "--env" => {
+                    let value = value()?;
+                    let (key, value) = value
+                        .split_once('=')
+                        .ok_or_else(|| anyhow!("Expected KEY=VALUE, got `{value}`"))?;
+                    parsed.launch.env.insert(key.into(), value.into());
+                }
+                "--cwd" => parsed.launch.cwd = Some(value()?.into()),
+                "--" => {
+                    parsed.launch.args.extend(args.by_ref());
+                    break;
+                }
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    std::process::exit(0);
+                }
+                arg => bail!("Unknown argument `{arg}`"),
+            }
+        }
+
+        if parsed.cmd != Cmd::Status && parsed.target.is_none() {
+            bail!("Missing required argument `--target`");
+        }
+
+        let has_launch_options = !parsed.launch.args.is_empty()
+            || !parsed.launch.env.is_empty()
+            || parsed.launch.cwd.is_some();
+        if parsed.cmd != Cmd::Run && has_launch_options {
+            bail!("Arguments, `--env` and `--cwd` are only supported for run");
+        }
+
+        if parsed.cmd == Cmd::Test && parsed.watch {
+            bail!("`--watch` is not supported for test");
+        }
+
+        Ok(parsed)
+    }
+
+    fn settings(&self) -> BuildSettings {
+        BuildSettings {
+            target: self.target.clone().unwrap_or_default(),
+            configuration: self.configuration.clone(),
+            scheme: self.scheme.clone(),
+        }
+    }
+
+    fn device(&self) -> DeviceLookup {
+        DeviceLookup {
+            name: self.device.clone(),
+            udid: None,
+        }
+    }
+
+    fn ops(&self) -> Operation {
+        if self.watch {
+            Operation::Watch
+        } else {
+            Operation::Once
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(err) = execute(args).await {
+        eprintln!("error: {err:#}");
+        std::process::exit(1);
+    }
+}
+
+async fn execute(args: Args) -> Result<()> {
+    let daemon = connect().await?;
+
+    if args.cmd == Cmd::Status {
+        let status = daemon.status(context::current()).await??;
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+
+    let root = match &args.root {
+        Some(root) => root.clone(),
+        None => std::env::current_dir()?,
+    };
+    let client = Client::headless(root.canonicalize().context("Project root")?);
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let events = follow(client.pid, stop.clone()).await?;
+
+    daemon
+        .register(
+            long_running(),
+            RegisterRequest {
+                client: client.clone(),
+                compile_swift_package: false,
+                swift_toolchain: None,
+            },
+        )
+        .await??;
+
+    let result = match args.cmd {
+        Cmd::Build => {
+            let req = BuildRequest {
+                client: client.clone(),
+                settings: args.settings(),
+                direction: Default::default(),
+                ops: args.ops(),
+            };
+            let result = daemon.build(long_running(), req.clone()).await?;
+            if args.watch && result.is_ok() {
+                wait_for_interrupt().await;
+                let req = BuildRequest {
+                    ops: Operation::Stop,
+                    ..req
+                };
+                daemon.build(context::current(), req).await??;
+            }
+            result.map(|_| ())
+        }
+        Cmd::Run => {
+            let req = RunRequest {
+                client: client.clone(),
+                settings: args.settings(),
+                device: args.device(),
+                direction: Default::default(),
+                ops: args.ops(),
+                launch: args.launch.clone(),
+            };
+            let key = req.to_string();
+            let result = daemon.run(long_running(), req.clone()).await?;
+            if args.watch && result.is_ok() {
+                wait_for_interrupt().await;
+                let req = RunRequest {
+                    ops: Operation::Stop,
+                    ..req
+                };
+                daemon.run(context::current(), req).await??;
+            } else if result.is_ok() {
+                tokio::select! {
+                    _ = wait_for_runner(&daemon, &key) => {},
+                    _ = wait_for_interrupt() => {},
+                }
+            }
+            result.map(|_| ())
+        }
+        Cmd::Test => {
+            let req = TestRequest {
+                client: client.clone(),
+                settings: args.settings(),
+                device: args.device(),
+                direction: Default::default(),
+            };
+            daemon.test(long_running(), req).await?.map(|_| ())
+        }
+        Cmd::Status => unreachable!(),
+    };
+
+    stop.store(true, Ordering::Relaxed);
+    events.await.ok();
+
+    let req = DropRequest {
+        client,
+        remove_client: true,
+    };
+    daemon.drop(context::current(), req).await??;
+
+    Ok(result?)
+}
+
+/// Connect to daemon, starting it when it's not already running
+async fn connect() -> Result<XBaseClient> {
+    let conn = match UnixStream::connect(DAEMON_SOCKET_PATH).await {
+        Ok(conn) => conn,
+        Err(_) => {
+            let daemon = std::env::current_exe()?.with_file_name("xbase");
+            Command::new(&daemon)
+                .spawn()
+                .with_context(|| format!("Start daemon {daemon:?}"))?;
+            tokio::time::sleep(Duration::new(1, 0)).await;
+            UnixStream::connect(DAEMON_SOCKET_PATH).await?
+        }
+    };
+
+    let codec_builder = LengthDelimitedCodec::builder();
+    let transport = transport::new(codec_builder.new_framed(conn), Json::default());
+    Ok(XBaseClient::new(Default::default(), transport).spawn())
+}
+
+/// Context for requests that are awaited until build or test finishes
+fn long_running() -> Context {
+    let mut ctx = context::current();
+    ctx.deadline = SystemTime::now() + Duration::from_secs(60 * 60);
+    ctx
+}
+
+/// Subscribe to daemon events reported to given client pid and print them until stopped
+async fn follow(pid: i32, stop: Arc<AtomicBool>) -> Result<JoinHandle<Result<()>>> {
+    let mut conn = UnixStream::connect(DAEMON_EVENTS_SOCKET_PATH)
+        .await
+        .context("Subscribe to daemon events")?;
+    let subscription = EventSubscription {
+        pid: Some(pid),
+        key: None,
+    };
+    let mut content = serde_json::to_vec(&subscription)?;
+    content.push(b'\n');
+    conn.write_all(&content).await?;
+
+    Ok(tokio::spawn(async move {
+        let mut lines = BufReader::new(conn).lines();
+
+        loop {
+            // NOTE: Once stopped, keep printing events until no more events are received
+            let line = if stop.load(Ordering::Relaxed) {
+                match tokio::time::timeout(Duration::from_millis(250), lines.next_line()).await {
+                    Ok(line) => line?,
+                    Err(_) => return Ok(()),
+                }
+            } else {
+                match tokio::time::timeout(Duration::from_millis(100), lines.next_line()).await {
+                    Ok(line) => line?,
+                    Err(_) => continue,
+                }
+            };
+
+            let line = match line {
+                Some(line) => line,
+                None => return Ok(()),
+            };
+            match serde_json::from_str::<ClientEvent>(&line)?.kind {
+                ClientEventKind::Log { lines } => lines.iter().for_each(|l| println!("{l}")),
+                ClientEventKind::Error { message } => eprintln!("{message}"),
+                ClientEventKind::Message { .. } | ClientEventKind::Status { .. } => {}
+            }
+        }
+    }))
+}
+
+/// Wait until daemon no longer reports runner with given key
+async fn wait_for_runner(daemon: &XBaseClient, key: &str) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        match daemon.status(context::current()).await {
+            Ok(Ok(status)) if status.runners.iter().any(|r| r.key == key) => continue,
+            _ => return,
+        }
+    }
+}
+
+async fn wait_for_interrupt() {
+    tokio::signal::ctrl_c().await.ok();
+}
+
+#[test]
+fn test_parse_args() {
+    let args = |v: &[&str]| Args::parse(v.iter().map(ToString::to_string));
+
+    let parsed = args(&["build", "--target", "Foo", "-c", "Release", "--watch"]).unwrap();
+    assert_eq!(parsed.cmd, Cmd::Build);
+    assert_eq!(parsed.target.as_deref(), Some("Foo"));
+    assert!(matches!(parsed.configuration, BuildConfiguration::Release));
+    assert!(parsed.watch);
+
+    let parsed = args(&["run", "-t", "Foo", "-c", "Staging", "-d", "iPhone 13"]).unwrap();
+    assert!(matches!(parsed.configuration, BuildConfiguration::Custom(ref c) if c == "Staging"));
+    assert_eq!(parsed.device.as_deref(), Some("iPhone 13"));
+
+    let parsed = args(&["run", "-t", "Foo", "-e", "A=1", "--", "--flag", "-t"]).unwrap();
+    assert_eq!(parsed.launch.env["A"], "1");
+    assert_eq!(parsed.launch.args, ["--flag", "-t"]);
+    assert!(args(&["build", "-t", "Foo", "--", "--flag"]).is_err());
+
+    assert!(args(&["status"]).is_ok());
+    assert!(args(&["build"]).is_err());
+    assert!(args(&["test", "-t", "FooTests", "--watch"]).is_err());
+    assert!(args(&["deploy"]).is_err());
+}
@@ -1,12 +1,20 @@
+mod diagnostic;
+mod event;
+
 use crate::constants::DAEMON_STATE;
 use crate::state::State;
+use crate::store::WatchRequest;
 use crate::watch::{Event, Watchable};
+use crate::Error;
 use crate::RequestHandler;
 use crate::Result;
 use async_trait::async_trait;
 use tokio::sync::MutexGuard;
 use xbase_proto::BuildRequest;
 
+pub use diagnostic::*;
+pub use event::*;
+
 #[async_trait]
 impl RequestHandler for BuildRequest {
     async fn handle(self) -> Result<()>
@@ -60,6 +68,7 @@ impl Watchable for BuildRequest {
         let nvim = state.clients.get(&self.client.pid)?;
         let logger = &mut nvim.logger();
 
+        logger.set_key(self);
         logger.set_title(format!(
             "{}:{}",
             if is_once { "Build" } else { "Rebuild" },
@@ -73,6 +82,9 @@ impl Watchable for BuildRequest {
             nvim.echo_err(msg).await?;
             log::error!("[target: {}] failed to be built", self.settings.target);
             log::error!("[ran: 'xcodebuild {}']", args.join(" "));
+            if is_once {
+                return Err(Error::Build(msg.to_string()));
+            }
         } else {
             log::info!("[target: {}] built successfully", self.settings.target);
         };
@@ -98,4 +110,8 @@ impl Watchable for BuildRequest {
     async fn discard(&self, _state: &MutexGuard<State>) -> Result<()> {
         Ok(())
     }
+
+    fn request(&self) -> Option<WatchRequest> {
+        Some(WatchRequest::Build(self.clone()))
+    }
 }
@@ -0,0 +1,115 @@
+//! Parse build errors and warnings into structured diagnostics.
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Diagnostic severity
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// Build diagnostic located in a source file
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub line: u32,
+    pub column: u32,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Parse `file:line:col: error|warning|note: message` out of a build log line.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (location, severity, message) = [
+            (": error: ", Severity::Error),
+            (": warning: ", Severity::Warning),
+            (": note: ", Severity::Note),
+        ]
+        .into_iter()
+        .find_map(|(sep, severity)| {
+            let (location, message) = line.split_once(sep)?;
+            Some((location, severity, message))
+        })?;
+
+        // NOTE: Strip leading log prefixes like `[Error] `
+        let location = location
+            .rsplit_once("] ")
+            .map(|(_, l)| l)
+            .unwrap_or(location)
+            .trim();
+
+        let mut parts = location.rsplitn(3, ':');
+        let (file, line, column) = match (parts.next()?, parts.next(), parts.next()) {
+            (column, Some(line), Some(file)) if column.parse::<u32>().is_ok() => {
+                (file, line.parse().ok()?, column.parse().ok()?)
+            }
+            (line, Some(file), _) => (file, line.parse().ok()?, 1),
+            _ => return None,
+        };
+
+        if file.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            file: file.into(),
+            line,
+            column,
+            severity,
+            message: message.trim().to_string(),
+        })
+    }
+
+    /// Remove diagnostics reported more then once at the same location, e.g. by xcodebuild
+    /// repeating errors in its summary.
+    pub fn dedup(diagnostics: &mut Vec<Self>) {
+        let mut seen = HashSet::new();
+        diagnostics.retain(|d| seen.insert((d.file.clone(), d.line, d.column, d.message.clone())));
+    }
+}
+
+#[test]
+fn test_diagnostic_parse() {
+    let diagnostic =
+        Diagnostic::parse("/tmp/App/Sources/View.swift:12:5: error: cannot find 'foo' in scope")
+            .unwrap();
+    assert_eq!(
+        diagnostic.file,
+        PathBuf::from("/tmp/App/Sources/View.swift")
+    );
+    assert_eq!(diagnostic.line, 12);
+    assert_eq!(diagnostic.column, 5);
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.message, "cannot find 'foo' in scope");
+
+    let diagnostic =
+        Diagnostic::parse("[Warning] /tmp/App/Sources/App.swift:3: warning: unused variable")
+            .unwrap();
+    assert_eq!(diagnostic.file, PathBuf::from("/tmp/App/Sources/App.swift"));
+    assert_eq!(diagnostic.line, 3);
+    assert_eq!(diagnostic.column, 1);
+    assert_eq!(diagnostic.severity, Severity::Warning);
+
+    assert!(Diagnostic::parse("[Compiling] App.swift").is_none());
+    assert!(Diagnostic::parse("error: no such module 'Foo'").is_none());
+}
+
+#[test]
+fn test_diagnostic_dedup() {
+    let line = "/tmp/App/Sources/View.swift:12:5: error: cannot find 'foo' in scope";
+    let other = "/tmp/App/Sources/View.swift:13:5: error: cannot find 'foo' in scope";
+    let mut diagnostics = [line, other, line]
+        .into_iter()
+        .filter_map(Diagnostic::parse)
+        .collect::<Vec<_>>();
+
+    Diagnostic::dedup(&mut diagnostics);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].line, 12);
+    assert_eq!(diagnostics[1].line, 13);
+}
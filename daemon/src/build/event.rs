@@ -0,0 +1,99 @@
+//! Typed events emitted while building, testing or preparing to run a target.
+use super::{Diagnostic, Severity};
+use process_stream::ProcessItem;
+
+/// Build event
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildEvent {
+    /// Plain build output
+    Output(String),
+    /// Build warning
+    Warning(String),
+    /// Build error
+    Error(String),
+    /// Build progress, e.g. `[Compiling] App.swift`
+    Progress(String),
+    /// Build process exited with code
+    Exit(i32),
+}
+
+impl BuildEvent {
+    /// Create event from a build output line
+    pub fn from_line<S: AsRef<str>>(line: S) -> Self {
+        let line = line.as_ref();
+        if let Some(diagnostic) = Diagnostic::parse(line) {
+            match diagnostic.severity {
+                Severity::Error => Self::Error(line.into()),
+                Severity::Warning => Self::Warning(line.into()),
+                Severity::Note => Self::Output(line.into()),
+            }
+        } else if line.starts_with("[Error]") {
+            Self::Error(line.into())
+        } else if line.starts_with("[Warning]") {
+            Self::Warning(line.into())
+        } else if line.starts_with('[') {
+            Self::Progress(line.into())
+        } else {
+            Self::Output(line.into())
+        }
+    }
+
+    /// Create event from exit code string, defaulting to failure when it can't be parsed
+    pub fn from_exit<S: AsRef<str>>(code: S) -> Self {
+        Self::Exit(code.as_ref().trim().parse().unwrap_or(1))
+    }
+
+    /// Get line of text carried by the event, if any
+    pub fn line(&self) -> Option<&str> {
+        match self {
+            Self::Output(line) | Self::Warning(line) | Self::Error(line) | Self::Progress(line) => {
+                Some(line)
+            }
+            Self::Exit(_) => None,
+        }
+    }
+
+    /// Whether the event is an exit event with non-zero code
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Exit(code) if *code != 0)
+    }
+}
+
+impl From<ProcessItem> for BuildEvent {
+    fn from(item: ProcessItem) -> Self {
+        match item {
+            ProcessItem::Exit(code) => Self::from_exit(code),
+            item => Self::from_line(item.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for BuildEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exit(code) => write!(f, "[Exit] {code}"),
+            event => write!(f, "{}", event.line().unwrap_or_default()),
+        }
+    }
+}
+
+#[test]
+fn test_build_event_from_line() {
+    use BuildEvent::*;
+    let error = "/tmp/App/View.swift:1:2: error: expected expression";
+    let warning = "/tmp/App/View.swift:3:2: warning: unused variable 'x'";
+
+    assert_eq!(BuildEvent::from_line(error), Error(error.into()));
+    assert_eq!(BuildEvent::from_line(warning), Warning(warning.into()));
+    assert_eq!(
+        BuildEvent::from_line("[Compiling] View.swift"),
+        Progress("[Compiling] View.swift".into())
+    );
+    assert_eq!(
+        BuildEvent::from_line("Test FAILED to compile"),
+        Output("Test FAILED to compile".into())
+    );
+    assert_eq!(BuildEvent::from_exit("65"), Exit(65));
+    assert!(BuildEvent::from_exit("65").is_failure());
+    assert!(!BuildEvent::from_exit("0").is_failure());
+}
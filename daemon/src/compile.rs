@@ -1,4 +1,8 @@
 //! Module for generating Compilation Database.
+mod database;
+
+pub use database::*;
+
 use crate::watch::Event;
 use crate::{state::State, Result};
 use std::path::PathBuf;
@@ -41,25 +45,32 @@ pub async fn ensure_server_config(root: &PathBuf) -> Result<()> {
 pub async fn ensure_server_support<'a>(
     state: &'a mut MutexGuard<'_, State>,
     client: &Client,
-    event: Option<&Event>,
+    events: &[Event],
 ) -> Result<bool> {
     let Client { root, pid, .. } = client;
     let ref name = client.abbrev_root();
 
     let compile_path = root.join(".compile");
     let compile_exists = compile_path.exists();
-    let is_swift_project = root.join("Package.swift").exists();
+    // NOTE: Swift packages are served by xbase only when opted in, see RegisterRequest
+    let is_swift_project = crate::project::is_swift_package(root);
+    let is_served = !is_swift_project || root.join("buildServer.json").exists();
 
-    if !is_swift_project && ensure_server_config(root).await.is_err() {
+    if is_served && ensure_server_config(root).await.is_err() {
         "fail to ensure build server configuration!"
             .pipe(|msg| state.clients.echo_err(root, name, msg))
             .await;
     }
 
-    if let Some(event) = event {
+    if !events.is_empty() {
         let project = state.projects.get_mut(root)?;
         let name = project.name().to_string();
-        if project.should_generate(event) {
+        // NOTE: The project is regenerated once for all events of a burst requiring it
+        let events = events
+            .iter()
+            .filter(|event| project.should_generate(event))
+            .collect::<Vec<_>>();
+        if !events.is_empty() {
             if let Err(e) = project.generate().await {
                 let mut lines = e
                     .to_string()
@@ -80,17 +91,19 @@ pub async fn ensure_server_support<'a>(
                 return Ok(false);
             };
 
-            project.update_compile_database().await?;
-            state
-                .clients
-                .get(&pid)?
-                .exec_lua("require'xbase.util'.reload_lsp_servers()", vec![])
-                .await?;
+            // NOTE: sourcekit helper notifies sourcekit-lsp of changed compile arguments
+            if let Err(err) = project.update_compile_database_incremental(&events).await {
+                "fail to regenerate compilation database!"
+                    .pipe(|msg| state.clients.echo_err(root, &name, msg))
+                    .await;
+                return Err(err);
+            }
+            project.update_source_roots().await;
             return Ok(true);
         }
     }
 
-    if !is_swift_project && !compile_exists {
+    if is_served && !compile_exists {
         "⚙ Generating compile database (may take few seconds) .."
             .pipe(|msg| state.clients.echo_msg(root, name, msg))
             .await;
@@ -111,6 +124,7 @@ pub async fn ensure_server_support<'a>(
 
             return Ok(false);
         }
+        state.projects.get_mut(&root)?.update_source_roots().await;
         Ok(true)
     } else {
         Ok(false)
@@ -0,0 +1,119 @@
+//! Helpers to partially update compile database.
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use xclog::XCCompileCommand;
+
+/// What a compile command compiles. Swift commands compile a whole module while clang commands
+/// compile a single file.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+enum CompileUnit {
+    Module(String),
+    File(PathBuf),
+}
+
+/// Get compile units of a given compile command
+fn compile_units(command: &XCCompileCommand) -> HashSet<CompileUnit> {
+    let flags = match command.compile_flags() {
+        Ok(flags) => flags,
+        Err(_) => return Default::default(),
+    };
+
+    flags
+        .into_iter()
+        .map(|(path, args)| {
+            let is_swift = path
+                .extension()
+                .map(|ext| ext == "swift")
+                .unwrap_or_default();
+            match module_name(&args.to_vec()) {
+                Some(module) if is_swift => CompileUnit::Module(module),
+                _ => CompileUnit::File(path),
+            }
+        })
+        .collect()
+}
+
+/// Get module name a file is compiled into
+fn module_name(args: &[String]) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == "-module-name" {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix("-fmodule-name=").map(ToString::to_string)
+        }
+    })
+}
+
+/// Get names of modules with files in the same directory as a given path.
+pub fn affected_modules(commands: &[XCCompileCommand], path: &Path) -> HashSet<String> {
+    let dir = match path.parent() {
+        Some(dir) => dir,
+        None => return Default::default(),
+    };
+
+    commands
+        .iter()
+        .flat_map(XCCompileCommand::compile_flags)
+        .flatten()
+        .filter(|(file, _)| file.parent() == Some(dir))
+        .filter_map(|(_, args)| module_name(&args.to_vec()))
+        .collect()
+}
+
+/// Get directories directly under root containing files compiled by given commands
+pub fn compiled_source_roots(commands: &[XCCompileCommand], root: &Path) -> Vec<PathBuf> {
+    let mut roots = commands
+        .iter()
+        .flat_map(XCCompileCommand::compile_flags)
+        .flatten()
+        .filter_map(|(file, _)| {
+            let mut components = file.strip_prefix(root).ok()?.components();
+            let dir = components.next()?;
+            // NOTE: Files at root are not grouped into a source directory
+            components.next()?;
+            Some(root.join(dir))
+        })
+        .collect::<Vec<_>>();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+/// Whether a module name is the name of given target. Xcode replaces characters not valid in
+/// identifiers with underscores.
+pub fn is_target_module(target: &str, module: &str) -> bool {
+    target
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .eq(module.chars())
+}
+
+/// Merge updated compile commands into existing ones, replacing commands compiling the same
+/// module or file and dropping commands of files that no longer exist.
+pub fn merge_compile_commands(
+    existing: Vec<XCCompileCommand>,
+    updates: Vec<XCCompileCommand>,
+) -> Vec<XCCompileCommand> {
+    let updated_units = updates
+        .iter()
+        .flat_map(compile_units)
+        .collect::<HashSet<_>>();
+
+    existing
+        .into_iter()
+        .filter(|command| {
+            compile_units(command).iter().all(|unit| match unit {
+                CompileUnit::Module(_) => !updated_units.contains(unit),
+                CompileUnit::File(path) => !updated_units.contains(unit) && path.exists(),
+            })
+        })
+        .chain(updates)
+        .collect()
+}
+
+#[test]
+fn test_is_target_module() {
+    assert!(is_target_module("Demo", "Demo"));
+    assert!(is_target_module("Demo-iOS", "Demo_iOS"));
+    assert!(!is_target_module("DemoTests", "Demo"));
+}
@@ -3,11 +3,19 @@ use std::path::PathBuf;
 /// Where the daemon socket path will be located
 pub static DAEMON_SOCKET_PATH: &str = "/tmp/xbase.socket";
 
+/// Where the daemon event socket path will be located
+pub static DAEMON_EVENTS_SOCKET_PATH: &str = "/tmp/xbase-events.socket";
+
 /// Where the daemon pid will be located
 pub static DAEMON_PID_PATH: &str = "/tmp/xbase.pid";
 
+/// Where client log files will be located
+pub static CLIENT_LOG_ROOT: &str = "/tmp/xbase-logs";
+
 pub type DaemonSharedState = std::sync::Arc<tokio::sync::Mutex<crate::state::State>>;
 
+pub type DaemonSharedStatus = std::sync::Arc<tokio::sync::RwLock<crate::state::StatusCache>>;
+
 lazy_static::lazy_static! {
     /// Where the server binary will be located.
     pub static ref SERVER_BINARY_PATH: PathBuf = {
@@ -20,6 +28,14 @@ lazy_static::lazy_static! {
         root
     };
 
+    /// Where the daemon state snapshot will be located
+    pub static ref DAEMON_SNAPSHOT_PATH: PathBuf = {
+        dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("Xbase")
+            .join("state.json")
+    };
+
     pub static ref DAEMON_STATE: DaemonSharedState = {
         use crate::state::State;
         use std::sync::Arc;
@@ -30,8 +46,13 @@ lazy_static::lazy_static! {
             clients: Default::default(),
             watcher: Default::default(),
             devices: Default::default(),
+            tests: Default::default(),
+            runners: Default::default(),
+            snapshot: crate::store::Snapshot::load(),
         }))
 
     };
 
+    /// Daemon status, updated on every state sync
+    pub static ref DAEMON_STATUS: DaemonSharedStatus = Default::default();
 }
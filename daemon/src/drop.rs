@@ -1,6 +1,6 @@
 use crate::{RequestHandler, Result};
 use async_trait::async_trait;
-use xbase_proto::DropRequest;
+use xbase_proto::{Client, DropRequest};
 
 #[async_trait]
 impl RequestHandler for DropRequest {
@@ -17,15 +17,40 @@ impl RequestHandler for DropRequest {
         let ref mut state = state.lock().await;
 
         if state.clients.contains_key(&client.pid) {
-            // NOTE: Should only be Some if no more client depend on it
-            if let Some(_) = state.projects.remove(&client).await? {
-                // NOTE: Remove project watchers
-                state.watcher.remove(&client);
+            // NOTE: Nested projects were registered along with client root
+            let mut roots = state.projects.nested_roots(&client.root, &client.pid);
+            if roots.is_empty() {
+                roots.push(client.root.clone());
             }
 
-            // NOTE: Try removing client with given pid
+            for root in roots {
+                let client = Client {
+                    root,
+                    ..client.clone()
+                };
+
+                // NOTE: Should only be Some if no more client depend on it
+                if let Some(_) = state.projects.remove(&client).await? {
+                    // NOTE: Remove project watchers
+                    state.watcher.remove(&client);
+                    // NOTE: Remove project test reports
+                    state.tests.remove(&client.root);
+                    // NOTE: Remove project runners
+                    state.runners.remove_by_root(&client.root);
+                }
+            }
+
+            // NOTE: Try removing client with given pid, otherwise only the dropped roots
             if self.remove_client {
                 state.clients.remove(&client);
+
+                let log_path = crate::util::fs::get_client_log_path(&client.pid);
+                if let Err(err) = tokio::fs::remove_file(&log_path).await {
+                    log::trace!("No client log removed at {log_path:?}: {err}");
+                }
+            } else {
+                let handle = state.clients.get_mut(&client.pid)?;
+                handle.roots.retain(|root| !root.starts_with(&client.root));
             }
 
             // NOTE: Sink state to all client vim.g.xbase.state
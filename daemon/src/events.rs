@@ -0,0 +1,71 @@
+//! Push client events to subscribers connected to the daemon event socket.
+//!
+//! Subscribers send a json encoded [`EventSubscription`] as first line, then receive newline
+//! delimited json encoded [`ClientEvent`]s matching it.
+use crate::Result;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use xbase_proto::{ClientEvent, ClientEventKind, EventSubscription};
+
+lazy_static::lazy_static! {
+    static ref EVENTS: broadcast::Sender<ClientEvent> = broadcast::channel(1024).0;
+}
+
+/// Publish event to subscribers
+pub fn publish(pid: i32, key: Option<&str>, kind: ClientEventKind) {
+    // NOTE: Errors only when there is no subscribers
+    EVENTS
+        .send(ClientEvent {
+            pid,
+            key: key.map(ToString::to_string),
+            kind,
+        })
+        .ok();
+}
+
+/// Accept subscribers on given socket path
+pub async fn serve(path: &str) -> Result<()> {
+    let listener = UnixListener::bind(path)?;
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(async move {
+                    if let Err(err) = subscribe(stream).await {
+                        log::debug!("Event subscriber dropped: {err}");
+                    }
+                });
+            }
+            Err(err) => log::error!("Fail to accept event subscriber: {err}"),
+        }
+    }
+}
+
+async fn subscribe(stream: UnixStream) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut receiver = EVENTS.subscribe();
+
+    let mut line = String::default();
+    BufReader::new(reader).read_line(&mut line).await?;
+    let subscription = serde_json::from_str::<EventSubscription>(&line)?;
+
+    log::info!("Event subscriber added: {subscription:?}");
+
+    loop {
+        let event = match receiver.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(count)) => {
+                log::warn!("Event subscriber skipped {count} events");
+                continue;
+            }
+            Err(RecvError::Closed) => return Ok(()),
+        };
+
+        if subscription.matches(&event) {
+            let mut content = serde_json::to_vec(&event)?;
+            content.push(b'\n');
+            writer.write_all(&content).await?;
+        }
+    }
+}
@@ -3,12 +3,15 @@ pub mod compile;
 pub mod constants;
 pub mod device;
 pub mod drop;
+pub mod events;
 pub mod nvim;
 pub mod project;
 pub mod register;
 pub mod run;
+pub mod sink;
 pub mod state;
 pub mod store;
+pub mod test;
 pub mod util;
 pub mod watch;
 
@@ -17,7 +20,7 @@ use std::pin::Pin;
 
 pub use xbase_proto::{Error, IntoResult, Result};
 pub type OutputStream = Pin<Box<dyn Stream<Item = ProcessItem> + Send>>;
-pub type StringStream = Pin<Box<dyn Stream<Item = String> + Send>>;
+pub type BuildStream = Pin<Box<dyn Stream<Item = build::BuildEvent> + Send>>;
 
 #[async_trait::async_trait]
 pub trait RequestHandler {
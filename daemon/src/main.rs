@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use tap::Pipe;
 use tokio::fs::{metadata, read_to_string, remove_file, write};
 use tokio::net::UnixListener;
-use xbase::util::pid;
+use xbase::util::{fs::get_client_log_path, pid};
 use xbase::{constants::*, RequestHandler};
 use xbase_proto::*;
 
@@ -14,20 +14,45 @@ struct Server;
 impl xbase_proto::XBase for Server {
     /// Register project root with a path to setup logs
     async fn register(self, _: Context, req: RegisterRequest) -> Result<PathBuf> {
+        let log_path = get_client_log_path(&req.client.pid);
         req.handle().await?;
-        Ok("/bin/cp".into())
+        Ok(log_path)
     }
     /// Build Project and get path to where to build log will be located
-    async fn build(self, _: Context, req: BuildRequest) -> Result<PathBuf> {
-        // NOTE: Required because of nvim-rs
-        tokio::spawn(async { req.handle().await });
-        Ok(PathBuf::default())
+    async fn build(self, _: Context, mut req: BuildRequest) -> Result<PathBuf> {
+        route(&mut req.client).await;
+        let log_path = get_client_log_path(&req.client.pid);
+        if req.client.is_headless() {
+            req.handle().await?;
+        } else {
+            // NOTE: Required because of nvim-rs
+            tokio::spawn(async { req.handle().await });
+        }
+        Ok(log_path)
     }
     /// Run Project and get path to where to Runtime log will be located
-    async fn run(self, _: Context, req: RunRequest) -> Result<PathBuf> {
-        // NOTE: Required because of nvim-rs
-        tokio::spawn(async { req.handle().await });
-        Ok(PathBuf::default())
+    async fn run(self, _: Context, mut req: RunRequest) -> Result<PathBuf> {
+        route(&mut req.client).await;
+        let log_path = get_client_log_path(&req.client.pid);
+        if req.client.is_headless() {
+            req.handle().await?;
+        } else {
+            // NOTE: Required because of nvim-rs
+            tokio::spawn(async { req.handle().await });
+        }
+        Ok(log_path)
+    }
+    /// Test Project and get path to where to test log will be located
+    async fn test(self, _: Context, mut req: TestRequest) -> Result<PathBuf> {
+        route(&mut req.client).await;
+        let log_path = get_client_log_path(&req.client.pid);
+        if req.client.is_headless() {
+            req.handle().await?;
+        } else {
+            // NOTE: Required because of nvim-rs
+            tokio::spawn(async { req.handle().await });
+        }
+        Ok(log_path)
     }
     /// Drop project root
     async fn drop(self, _: Context, req: DropRequest) -> Result<()> {
@@ -35,7 +60,22 @@ impl xbase_proto::XBase for Server {
         tokio::spawn(async { req.handle().await });
         Ok(())
     }
+    /// Get daemon projects, watchers, running processes and connected clients
+    async fn status(self, _: Context) -> Result<Status> {
+        let status = DAEMON_STATUS.clone();
+        let status = status.read().await;
+        Ok(status.status().await)
+    }
+}
+/// Route client to the nearest registered project enclosing its root, e.g. nested packages
+async fn route(client: &mut Client) {
+    let state = DAEMON_STATE.clone();
+    let state = state.lock().await;
+    if let Some(root) = state.projects.nearest_root(&client.root) {
+        client.root = root.clone();
+    }
 }
+
 #[tokio::main]
 async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     ensure_single_instance().await?;
@@ -45,6 +85,15 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     log::setup("/tmp", "xbase-daemon.log", Level::DEBUG, true)?;
     log::info!("Started");
 
+    tokio::spawn(async {
+        if let Err(err) = xbase::events::serve(DAEMON_EVENTS_SOCKET_PATH).await {
+            log::error!("Fail to serve events: {err}");
+        }
+    });
+
+    // NOTE: Clients of previous daemon instance keep running, e.g. after a daemon upgrade
+    tokio::spawn(xbase::store::Snapshot::rehydrate());
+
     loop {
         if let Ok((s, _)) = listener.accept().await {
             tokio::spawn(async move {
@@ -75,6 +124,7 @@ async fn ensure_single_instance() -> Result<()> {
         }
         remove_file(DAEMON_PID_PATH).await.ok();
     }
+    remove_file(DAEMON_EVENTS_SOCKET_PATH).await.ok();
     write(DAEMON_PID_PATH, std::process::id().to_string()).await?;
     Ok(())
 }
@@ -1,30 +1,27 @@
-mod logger;
-
-use std::path::PathBuf;
-
+use crate::build::Diagnostic;
+use crate::sink::{BuildStatus, ClientSink};
 use crate::Result;
 use nvim_rs::{compat::tokio::Compat, create::tokio::new_path as connect, rpc::handler::Dummy};
-use serde::{Deserialize, Serialize};
-use xbase_proto::Client;
-
-pub use logger::*;
+use nvim_rs::{Buffer, Neovim};
+use tokio::sync::Mutex;
+use xbase_proto::{BufferDirection, Client};
 
 type NvimConnection = Compat<tokio::io::WriteHalf<parity_tokio_ipc::Connection>>;
 pub type NvimWindow = nvim_rs::Window<NvimConnection>;
 
-#[derive(Deserialize, Serialize)]
-pub struct NvimClient {
-    pub pid: i32,
-    pub roots: Vec<PathBuf>,
-    #[serde(skip)]
-    pub conn: Option<nvim_rs::Neovim<NvimConnection>>,
+/// Sink reporting client output to a running neovim instance
+pub struct NvimSink {
+    conn: Neovim<NvimConnection>,
     pub log_bufnr: i64,
+    /// Log buffer line count, zero when empty. Kept locally to avoid querying it on each append
+    line_count: Mutex<i64>,
+    /// Last window found showing the log buffer
+    log_win: Mutex<Option<NvimWindow>>,
 }
 
-impl NvimClient {
+impl NvimSink {
     pub async fn new(client: &Client) -> Result<Self> {
-        let Client { root, pid, address } = client;
-        let (nvim, _) = connect(address, Dummy::new()).await?;
+        let (nvim, _) = connect(&client.address, Dummy::new()).await?;
         let buf = nvim.create_buf(false, true).await?;
         let log_bufnr = buf.get_number().await?;
         let script = format!("let g:xbase_log_bufnr={log_bufnr}");
@@ -36,89 +33,174 @@ impl NvimClient {
         );
         _ = (a?, b?, c?);
 
-        Ok(NvimClient {
-            pid: *pid,
-            roots: vec![root.to_path_buf()],
-            conn: nvim.into(),
+        Ok(Self {
+            conn: nvim,
             log_bufnr,
+            line_count: Mutex::new(0),
+            log_win: Default::default(),
         })
     }
 
-    pub async fn set_watching(&self, is_watching: bool) -> Result<()> {
-        if is_watching {
-            self.exec("let g:xbase_watch_build_status='watching'", false)
-                .await?;
-        } else {
-            self.exec("let g:xbase_watch_build_status=''", false)
-                .await?;
+    fn buf(&self) -> Buffer<NvimConnection> {
+        Buffer::new(self.log_bufnr.into(), self.conn.clone())
+    }
+
+    /// Get logger window if it's available and whether is currently focused.
+    pub async fn win(&self) -> Option<(bool, NvimWindow)> {
+        let mut log_win = self.log_win.lock().await;
+
+        // NOTE: Windows are only listed when the last log window was closed
+        let is_valid = match log_win.as_ref() {
+            Some(win) => win.is_valid().await.unwrap_or_default(),
+            None => false,
+        };
+        if !is_valid {
+            *log_win = None;
+            for win in self.list_wins().await.ok()?.into_iter() {
+                let buf = win.get_buf().await.ok()?;
+                if buf.get_number().await.ok()? == self.log_bufnr {
+                    *log_win = Some(win);
+                    break;
+                }
+            }
         }
 
-        Ok(())
+        let win = log_win.clone()?;
+        let curr = self.get_current_win().await.ok()?;
+        let is_focused = curr.get_number().await.ok()? == win.get_number().await.ok()?;
+        Some((is_focused, win))
     }
 
-    pub async fn sync_state(&self, update_state_script: &str) -> Result<()> {
-        self.exec_lua(update_state_script, vec![]).await?;
-        Ok(())
+    async fn get_window_direction(&self, direction: Option<&BufferDirection>) -> Result<String> {
+        use std::str::FromStr;
+        use tap::Pipe;
+        let ref bufnr = self.log_bufnr;
+
+        if let Some(direction) = direction {
+            return Ok(direction.to_nvim_command(*bufnr));
+        };
+
+        match "return require'xbase.config'.values.default_log_buffer_direction"
+            .pipe(|str| self.exec_lua(str, vec![]))
+            .await?
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Unable to covnert value to string"))?
+            .pipe(BufferDirection::from_str)
+            .map(|d| d.to_nvim_command(*bufnr))
+        {
+            Ok(open_command) => open_command,
+            Err(e) => {
+                log::error!("Unable to convert value to string {e}");
+                BufferDirection::Horizontal.to_nvim_command(*bufnr)
+            }
+        }
+        .pipe(Ok)
     }
 }
 
-impl NvimClient {
-    async fn log(&self, level: &str, scope: &str, value: impl ToString) -> Result<()> {
-        for line in value.to_string().split("\n") {
-            let msg = format!(
-                r#"require'xbase.log'.{level}("[{scope}]: {}")"#,
-                line.escape_default()
-            );
-            self.exec_lua(&msg, Vec::default()).await?;
+#[async_trait::async_trait]
+impl ClientSink for NvimSink {
+    // TODO(logger): always show current new logs in middle of the window
+    async fn append(&self, lines: &[String]) -> Result<()> {
+        let buf = self.buf();
+        let win_info = self.win().await;
+        let mut count = self.line_count.lock().await;
+
+        if *count == 0 {
+            buf.set_lines(0, -1, false, lines.to_vec()).await?;
+        } else {
+            buf.set_lines(-1, -1, false, lines.to_vec()).await?;
+        }
+
+        *count += lines.len() as i64;
+        let c = *count;
+
+        if let Some((focused, win)) = win_info {
+            if !focused {
+                win.set_cursor((c, 0)).await?;
+            } else {
+                let (current, _) = win.get_cursor().await?;
+                let diff = c - current;
+                if (1..=lines.len() as i64 + 1).contains(&diff) {
+                    win.set_cursor((c, 0)).await?;
+                }
+            }
         }
 
         Ok(())
     }
-    fn inner(&self) -> &nvim_rs::Neovim<NvimConnection> {
-        self.conn.as_ref().unwrap()
-    }
-    pub async fn log_info(&self, scope: &str, msg: impl ToString) -> Result<()> {
-        self.log("info", scope, msg).await
-    }
-    pub async fn log_debug(&self, scope: &str, msg: impl ToString) -> Result<()> {
-        self.log("debug", scope, msg).await
-    }
-    pub async fn log_error(&self, scope: &str, msg: impl ToString) -> Result<()> {
-        self.log("error", scope, msg).await
-    }
-    pub async fn log_trace(&self, scope: &str, msg: impl ToString) -> Result<()> {
-        self.log("trace", scope, msg).await
+
+    async fn clear(&self) -> Result<()> {
+        let mut count = self.line_count.lock().await;
+        self.buf().set_lines(0, -1, false, vec![]).await?;
+        *count = 0;
+        Ok(())
     }
-    pub async fn log_warn(&self, scope: &str, msg: impl ToString) -> Result<()> {
-        self.log("warn", scope, msg).await
+
+    async fn set_status(&self, status: BuildStatus) -> Result<()> {
+        let status = match status {
+            BuildStatus::Idle => String::default(),
+            status => status.to_string(),
+        };
+        self.exec(&format!("let g:xbase_watch_build_status='{status}'"), false)
+            .await?;
+        Ok(())
     }
-    pub async fn echo_msg(&self, msg: &str) -> Result<()> {
-        self.exec(msg, false).await?;
+
+    async fn echo_msg(&self, msg: &str) -> Result<()> {
+        self.exec(&format!("echo '{}'", msg.replace('\'', "''")), false)
+            .await?;
         Ok(())
     }
-    pub async fn echo_err(&self, msg: &str) -> Result<()> {
+
+    async fn echo_err(&self, msg: &str) -> Result<()> {
         Ok(self.err_writeln(msg).await?)
     }
-}
 
-impl std::fmt::Debug for NvimClient {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("NvimClient")
-            .field("pid", &self.pid)
-            .field("roots", &self.roots)
-            .finish()
+    async fn sync_state(&self, state: &str) -> Result<()> {
+        let script = format!("vim.g.xbase= vim.json.decode([[{state}]])");
+        self.exec_lua(&script, vec![]).await?;
+        Ok(())
     }
-}
 
-impl std::ops::Deref for NvimClient {
-    type Target = nvim_rs::Neovim<NvimConnection>;
-    fn deref(&self) -> &Self::Target {
-        &self.conn.as_ref().unwrap()
+    /// Replace client quickfix list and buffer diagnostics with build diagnostics
+    async fn set_diagnostics(&self, diagnostics: &[Diagnostic]) -> Result<()> {
+        let diagnostics = serde_json::to_string(diagnostics)?;
+        let script =
+            format!("require'xbase.util'.set_diagnostics(vim.json.decode([==[{diagnostics}]==]))");
+        self.exec_lua(&script, vec![]).await?;
+        Ok(())
+    }
+
+    async fn open_log(&self, direction: Option<&BufferDirection>) -> Result<()> {
+        if self.win().await.is_some() {
+            return Ok(());
+        }
+
+        log::trace!("Openning a new window");
+
+        let open_cmd = self.get_window_direction(direction).await?;
+
+        // TODO(nvim): setup autocmd for buffer type
+        let setup_script = format!(
+            r#"
+            {open_cmd}
+            setlocal nonumber norelativenumber
+            setlocal scrolloff=3
+            "#
+        );
+
+        self.exec(&setup_script, false).await?;
+        self.exec("wincmd w", false).await?;
+        self.exec("call feedkeys('zt')", false).await?;
+
+        Ok(())
     }
 }
 
-impl std::ops::DerefMut for NvimClient {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.conn.as_mut().unwrap()
+impl std::ops::Deref for NvimSink {
+    type Target = Neovim<NvimConnection>;
+    fn deref(&self) -> &Self::Target {
+        &self.conn
     }
 }
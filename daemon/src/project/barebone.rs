@@ -1,20 +1,24 @@
 use super::*;
 use crate::watch::Event;
-use crate::{Error, Result};
+use crate::Result;
 use serde::Serialize;
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use xbase_proto::Client;
-use xcodeproj::{pbxproj::PBXTargetPlatform, XCodeProject};
+use xcodeproj::pbxproj::PBXTargetPlatform;
 
 #[derive(Debug, Serialize, Default)]
 #[serde(default)]
 pub struct BareboneProject {
     root: PathBuf,
     targets: HashMap<String, PBXTargetPlatform>,
+    schemes: HashMap<String, Scheme>,
     clients: Vec<i32>,
     watchignore: Vec<String>,
     #[serde(skip)]
-    xcodeproj: XCodeProject,
+    xcodeprojs: XCodeProjects,
+    #[serde(skip)]
+    source_roots: Vec<PathBuf>,
 }
 
 impl ProjectData for BareboneProject {
@@ -23,13 +27,29 @@ impl ProjectData for BareboneProject {
     }
 
     fn name(&self) -> &str {
-        &self.xcodeproj.name()
+        self.xcodeprojs.name()
     }
 
     fn targets(&self) -> &HashMap<String, PBXTargetPlatform> {
         &self.targets
     }
 
+    fn workspace(&self) -> Option<&PathBuf> {
+        self.xcodeprojs.workspace.as_ref()
+    }
+
+    fn target_xcodeproj(&self, target: &str) -> Option<&PathBuf> {
+        self.xcodeprojs.owners.get(target)
+    }
+
+    fn shadowed_targets(&self) -> HashMap<String, Vec<PathBuf>> {
+        self.xcodeprojs.shadowed.clone()
+    }
+
+    fn schemes(&self) -> &HashMap<String, Scheme> {
+        &self.schemes
+    }
+
     fn clients(&self) -> &Vec<i32> {
         &self.clients
     }
@@ -49,33 +69,19 @@ impl ProjectBuild for BareboneProject {}
 #[async_trait::async_trait]
 impl ProjectRun for BareboneProject {}
 
+#[async_trait::async_trait]
+impl ProjectTest for BareboneProject {}
+
 #[async_trait::async_trait]
 impl ProjectCompile for BareboneProject {
     async fn update_compile_database(&self) -> Result<()> {
-        use xclog::XCCompilationDatabase as CC;
-
-        let (name, root) = (self.name(), self.root());
+        let root = self.root();
         let cache_root = self.build_cache_root()?;
         let mut args = self.compile_arguments();
 
         args.push(format!("SYMROOT={cache_root}"));
 
-        let xcworkspace = format!("{name}.xcworkspace");
-
-        if self.root().join(&xcworkspace).exists() {
-            args.extend_from_slice(&[
-                "-workspace".into(),
-                xcworkspace,
-                "-scheme".into(),
-                name.into(),
-            ]);
-        } else {
-            args.extend_from_slice(&["-project".into(), format!("{name}.xcodeproj")]);
-        }
-
-        log::info!("xcodebuild {}", args.join(" "));
-
-        let compile_commands = CC::generate(&root, &args).await?.to_vec();
+        let compile_commands = self.generate_compile_commands(args).await?;
         let json = serde_json::to_vec_pretty(&compile_commands)?;
 
         tokio::fs::write(root.join(".compile"), &json).await?;
@@ -87,7 +93,15 @@ impl ProjectCompile for BareboneProject {
 #[async_trait::async_trait]
 impl ProjectGenerate for BareboneProject {
     fn should_generate(&self, event: &Event) -> bool {
-        event.is_create_event() || event.is_remove_event() || event.is_rename_event()
+        self.is_source_tree_update(event)
+    }
+
+    fn source_roots(&self) -> &[PathBuf] {
+        &self.source_roots
+    }
+
+    async fn update_source_roots(&mut self) {
+        self.source_roots = xcodeproj_source_roots(&self.root, &self.xcodeprojs).await;
     }
 
     async fn generate(&mut self) -> Result<()> {
@@ -109,20 +123,11 @@ impl Project for BareboneProject {
             ..Self::default()
         };
 
-        let xcodeproj_paths = project.get_xcodeproj_paths()?;
-        if xcodeproj_paths.len() > 1 {
-            log::warn!(
-                "Found more then on xcodeproj, using {:?}",
-                xcodeproj_paths[0]
-            );
-        }
-
-        if xcodeproj_paths.is_empty() {
-            return Err(Error::DefinitionLocating);
-        };
-
-        project.xcodeproj = XCodeProject::new(&xcodeproj_paths[0])?;
-        project.targets = project.xcodeproj.targets_platform();
+        project.xcodeprojs = XCodeProjects::load(root, project.get_xcodeproj_paths()?)?;
+        project.targets = project.xcodeprojs.targets.clone();
+        project.schemes =
+            Scheme::discover(&project.xcodeprojs.containers(), project.targets.keys());
+        project.update_source_roots().await;
 
         log::info!("targets: {:?}", project.targets());
         Ok(project)
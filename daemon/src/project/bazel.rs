@@ -0,0 +1,549 @@
+mod aquery;
+mod query;
+
+use super::swift::CompileCommand;
+use super::*;
+use crate::util::fs::which;
+use crate::watch::Event;
+use crate::{Error, Result};
+use process_stream::Process;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+use xbase_proto::{BuildConfiguration, Client};
+use xcodeproj::pbxproj::PBXTargetPlatform;
+
+pub use query::*;
+
+/// Files defining a bazel workspace root
+pub const WORKSPACE_FILES: [&str; 3] = ["WORKSPACE", "WORKSPACE.bazel", "MODULE.bazel"];
+
+#[derive(Debug, Serialize, Default)]
+#[serde(default)]
+pub struct BazelProject {
+    name: String,
+    root: PathBuf,
+    targets: HashMap<String, PBXTargetPlatform>,
+    tests: Vec<String>,
+    /// Targets kinds by label, including tests
+    kinds: HashMap<String, BazelTargetKind>,
+    schemes: HashMap<String, Scheme>,
+    clients: Vec<i32>,
+    watchignore: Vec<String>,
+    #[serde(skip)]
+    bazel: String,
+    #[serde(skip)]
+    query: HashMap<String, BazelTarget>,
+    /// Flags of the last build, reused to query compile actions of the same configuration
+    #[serde(skip)]
+    build_flags: Mutex<Vec<String>>,
+}
+
+impl ProjectData for BazelProject {
+    fn root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn targets(&self) -> &HashMap<String, PBXTargetPlatform> {
+        &self.targets
+    }
+
+    fn schemes(&self) -> &HashMap<String, Scheme> {
+        &self.schemes
+    }
+
+    fn target_schemes(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    fn clients(&self) -> &Vec<i32> {
+        &self.clients
+    }
+
+    fn clients_mut(&mut self) -> &mut Vec<i32> {
+        &mut self.clients
+    }
+
+    fn watchignore(&self) -> &Vec<String> {
+        &self.watchignore
+    }
+}
+
+#[async_trait::async_trait]
+impl ProjectBuild for BazelProject {
+    fn build(
+        &self,
+        cfg: &BuildSettings,
+        device: Option<&Device>,
+    ) -> Result<(BuildStream, Vec<String>)> {
+        log::info!("Building {}", cfg.target);
+
+        self.target(&cfg.target)?;
+        *self.build_flags.lock().unwrap() = self.bazel_flags(cfg, device);
+        self.bazel(self.bazel_arguments("build", cfg, device))
+    }
+}
+
+/// Targets are run from their build outputs rather than with `bazel run`, which picks its own
+/// simulator and holds bazel server lock while the process is running, blocking other builds.
+/// Outputs are read with `bazel cquery --output=files` once the build succeeded, then run with
+/// xbase runners, i.e. installed and launched on the selected simulator or run on the host.
+#[async_trait::async_trait]
+impl ProjectRun for BazelProject {
+    fn get_runner(
+        &self,
+        cfg: &BuildSettings,
+        device: Option<&Device>,
+    ) -> Result<(Box<dyn Runner + Send + Sync>, BuildStream, Vec<String>)> {
+        let target = self.target(&cfg.target)?.clone();
+        match target.kind {
+            BazelTargetKind::Executable | BazelTargetKind::Application => {}
+            kind => {
+                let msg = format!("{} is a {kind} target and can't be run", target.label);
+                return Err(Error::Run(msg));
+            }
+        }
+
+        if target.requires_simulator() && device.is_none() {
+            let msg = format!("{} can only be run on a simulator", target.label);
+            return Err(Error::Run(msg));
+        }
+
+        let (build_stream, args) = self.build(cfg, device)?;
+
+        let mut cquery = self.bazel_arguments("cquery", cfg, device);
+        cquery.push("--output=files".into());
+
+        let (bazel, root, device) = (self.bazel.clone(), self.root.clone(), device.cloned());
+
+        // NOTE: Outputs are only resolved once the build succeeded
+        let runner = LazyRunner::new(move || {
+            let (bazel, root, device) = (bazel.clone(), root.clone(), device.clone());
+            let (target, cquery) = (target.clone(), cquery.clone());
+            async move {
+                let output = Command::new(&bazel)
+                    .args(&cquery)
+                    .current_dir(&root)
+                    .stdin(std::process::Stdio::null())
+                    .output()
+                    .await?;
+
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    return Err(Error::Run(format!(
+                        "Getting target outputs failed {stderr}"
+                    )));
+                }
+
+                let outputs = String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .map(|line| root.join(line.trim()))
+                    .collect::<Vec<_>>();
+
+                let runner: Box<dyn Runner + Send + Sync> = match target.kind {
+                    BazelTargetKind::Application => {
+                        let app = outputs
+                            .into_iter()
+                            .find(|path| path.extension().map_or(false, |ext| ext == "app"))
+                            .ok_or_else(|| {
+                                Error::Run(format!("No app bundle found for {}", target.label))
+                            })?;
+
+                        match device {
+                            Some(device) if target.requires_simulator() => {
+                                Box::new(SimulatorRunner {
+                                    device,
+                                    app_id: bundle_identifier(&app).await?,
+                                    output_dir: app,
+                                })
+                            }
+                            _ => {
+                                let name = app.file_stem().unwrap_or_default().to_owned();
+                                let bin = app.join("Contents").join("MacOS").join(name);
+                                Box::new(BinRunner::from_path(&bin))
+                            }
+                        }
+                    }
+                    _ => match outputs.into_iter().next() {
+                        Some(bin) => Box::new(BinRunner::from_path(&bin)),
+                        None => {
+                            let msg = format!("No executable found for {}", target.label);
+                            return Err(Error::Run(msg));
+                        }
+                    },
+                };
+
+                Ok(runner)
+            }
+            .boxed()
+        });
+
+        Ok((Box::new(runner), build_stream, args))
+    }
+}
+
+#[async_trait::async_trait]
+impl ProjectTest for BazelProject {
+    fn test(
+        &self,
+        cfg: &BuildSettings,
+        device: Option<&Device>,
+    ) -> Result<(BuildStream, Vec<String>)> {
+        log::info!("Testing {}", cfg.target);
+
+        let target = self.target(&cfg.target)?;
+        if target.kind != BazelTargetKind::Test {
+            let msg = format!(
+                "{} is a {} target, not a test target",
+                cfg.target, target.kind
+            );
+            return Err(Error::Build(msg));
+        }
+
+        let mut args = self.bazel_arguments("test", cfg, device);
+        args.push("--test_output=errors".into());
+
+        self.bazel(args)
+    }
+}
+
+#[async_trait::async_trait]
+impl ProjectCompile for BazelProject {
+    /// Generate compile database from `bazel aquery` compile actions of all targets
+    async fn update_compile_database(&self) -> Result<()> {
+        let compile_commands = self
+            .compile_commands(&aquery::compile_actions_query("//..."))
+            .await?;
+
+        log::debug!(
+            "[{}] compiled successfully ({} commands)",
+            self.name(),
+            compile_commands.len()
+        );
+
+        let json = serde_json::to_vec_pretty(&compile_commands)?;
+        tokio::fs::write(self.root().join(".compile"), &json).await?;
+
+        Ok(())
+    }
+
+    /// Update compile commands of the packages containing the changed files only
+    async fn update_compile_database_incremental(&self, events: &[&Event]) -> Result<()> {
+        let root = self.root();
+        let existing = tokio::fs::read(root.join(".compile"))
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok());
+
+        let mut packages = vec![];
+        for event in events {
+            match self.package_label(event.path()).await {
+                Some(package) => packages.push(format!("{package}:all")),
+                None => return self.update_compile_database().await,
+            }
+        }
+        packages.sort();
+        packages.dedup();
+
+        let existing = match existing {
+            Some(existing) if !packages.is_empty() => existing,
+            _ => return self.update_compile_database().await,
+        };
+
+        let query = aquery::compile_actions_query_shallow(&packages.join(" + "));
+        let updates = self.compile_commands(&query).await?;
+        let compile_commands = CompileCommand::merge(existing, updates);
+
+        let json = serde_json::to_vec_pretty(&compile_commands)?;
+        tokio::fs::write(root.join(".compile"), &json).await?;
+
+        Ok(())
+    }
+}
+
+impl BazelProject {
+    /// Get compile commands of compile actions matching the given aquery expression
+    async fn compile_commands(&self, query: &str) -> Result<Vec<CompileCommand>> {
+        let root = self.root();
+        let mut args = vec!["aquery".to_string()];
+        args.extend(self.build_flags.lock().unwrap().iter().cloned());
+        args.extend([
+            "--output=jsonproto".into(),
+            "--include_artifacts=false".into(),
+            "--include_param_files".into(),
+            query.into(),
+        ]);
+
+        log::info!("bazel {}", args.join(" "));
+
+        let output = Command::new(&self.bazel)
+            .args(&args)
+            .current_dir(root)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Build(stderr.to_string()));
+        }
+
+        let actions = aquery::compile_arguments(&output.stdout)?;
+        let execution_root = PathBuf::from(self.info("execution_root").await?);
+        let developer_dir = command_output("xcode-select", &["-p"])
+            .await
+            .unwrap_or_default();
+
+        let mut sdk_paths = HashMap::new();
+        for args in actions.iter() {
+            let sdk = aquery::sdk_name(args);
+            if !sdk_paths.contains_key(sdk) {
+                let path = command_output("xcrun", &["--sdk", sdk, "--show-sdk-path"]).await;
+                sdk_paths.insert(sdk, path.unwrap_or_default());
+            }
+        }
+
+        let compile_commands = actions
+            .into_iter()
+            .map(|args| aquery::substitute(args, &developer_dir, &sdk_paths))
+            .filter_map(|args| CompileCommand::from_args(args, &execution_root))
+            .map(|mut command| {
+                // NOTE: Sources are relative to execution root, which links workspace files
+                let resolve = |path: PathBuf| match root.join(&path) {
+                    source if source.exists() => source,
+                    _ => execution_root.join(path),
+                };
+                command.file = command.file.map(resolve);
+                command.files = command
+                    .files
+                    .map(|files| files.into_iter().map(resolve).collect());
+                command
+            })
+            .collect::<Vec<_>>();
+
+        Ok(compile_commands)
+    }
+
+    /// Get label of the package containing the given path, e.g. `//App/Sources`
+    async fn package_label(&self, path: &Path) -> Option<String> {
+        let root = self.root();
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(root) {
+                break;
+            }
+            for name in ["BUILD", "BUILD.bazel"] {
+                if tokio::fs::metadata(dir.join(name)).await.is_ok() {
+                    let package = dir.strip_prefix(root).ok()?.display().to_string();
+                    return Some(format!("//{package}"));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[async_trait::async_trait]
+impl ProjectGenerate for BazelProject {
+    fn should_generate(&self, event: &Event) -> bool {
+        let file_name = event.file_name().as_str();
+        let is_build_file = matches!(file_name, "BUILD" | "BUILD.bazel")
+            || WORKSPACE_FILES.contains(&file_name)
+            || file_name.ends_with(".bzl");
+
+        is_build_file
+            && (event.is_content_update_event()
+                || event.is_create_event()
+                || event.is_remove_event()
+                || event.is_rename_event())
+    }
+
+    /// Query bazel targets
+    async fn generate(&mut self) -> Result<()> {
+        let output = Command::new(&self.bazel)
+            .args(["query", "--output=label_kind", TARGETS_QUERY])
+            .current_dir(self.root())
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            log::error!("Fail to query bazel targets {stderr}");
+            return Err(Error::Generate(stderr.to_string()));
+        }
+
+        let targets = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(BazelTarget::parse)
+            .collect::<Vec<_>>();
+
+        self.targets = targets
+            .iter()
+            .filter(|target| target.kind != BazelTargetKind::Test)
+            .map(|target| (target.label.clone(), target.platform.clone()))
+            .collect();
+
+        self.tests = targets
+            .iter()
+            .filter(|target| target.kind == BazelTargetKind::Test)
+            .map(|target| target.label.clone())
+            .collect();
+        self.tests.sort();
+
+        self.kinds = targets
+            .iter()
+            .map(|target| (target.label.clone(), target.kind))
+            .collect();
+
+        self.query = targets
+            .into_iter()
+            .map(|target| (target.label.clone(), target))
+            .collect();
+
+        log::info!("(name: {:?}, targets: {:?})", self.name(), self.targets());
+        log::info!("(name: {:?}, tests: {:?})", self.name(), self.tests);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Project for BazelProject {
+    async fn new(client: &Client) -> Result<Self> {
+        let Client { root, pid, .. } = client;
+
+        let mut watchignore = generate_watchignore(root).await;
+        watchignore.extend(["**/bazel-*".into(), "**/bazel-*/**".into()]);
+
+        let mut project = Self {
+            name: root
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            root: root.clone(),
+            bazel: which("bazel").or_else(|_| which("bazelisk"))?,
+            watchignore,
+            clients: vec![pid.clone()],
+            ..Self::default()
+        };
+
+        *project.build_flags.lock().unwrap() = project.bazel_flags(
+            &BuildSettings {
+                target: Default::default(),
+                configuration: BuildConfiguration::Debug,
+                scheme: None,
+            },
+            None,
+        );
+        project.generate().await?;
+
+        Ok(project)
+    }
+}
+
+impl BazelProject {
+    /// Run bazel with given arguments
+    fn bazel(&self, args: Vec<String>) -> Result<(BuildStream, Vec<String>)> {
+        let mut process = Process::new(&self.bazel);
+
+        process.args(&args);
+        process.current_dir(self.root());
+
+        let stream = Box::pin(process.spawn_and_stream()?);
+
+        Ok((process_build_events(stream), args))
+    }
+
+    /// Get bazel command arguments for given build settings and optionally a device
+    fn bazel_arguments(
+        &self,
+        command: &str,
+        cfg: &BuildSettings,
+        device: Option<&Device>,
+    ) -> Vec<String> {
+        let mut args = vec![command.to_string()];
+        args.extend(self.bazel_flags(cfg, device));
+        args.push(cfg.target.clone());
+        args
+    }
+
+    /// Get bazel configuration flags for given build settings and optionally a device
+    fn bazel_flags(&self, cfg: &BuildSettings, device: Option<&Device>) -> Vec<String> {
+        let mut args = vec![];
+
+        args.push(match &cfg.configuration {
+            BuildConfiguration::Debug => "--compilation_mode=dbg".into(),
+            BuildConfiguration::Release => "--compilation_mode=opt".into(),
+            BuildConfiguration::Custom(config) => format!("--config={config}"),
+        });
+
+        // NOTE: Application bundles are needed as directories to be installed on simulators
+        args.push("--define=apple.experimental.tree_artifact_outputs=1".into());
+
+        if let Some(device) = device {
+            let cpu = match std::env::consts::ARCH {
+                "aarch64" => "sim_arm64",
+                _ => "x86_64",
+            };
+            match device.platform {
+                PBXTargetPlatform::IOS => args.push(format!("--ios_multi_cpus={cpu}")),
+                PBXTargetPlatform::TvOS => args.push(format!("--tvos_cpus={cpu}")),
+                _ => {}
+            }
+        }
+
+        args
+    }
+
+    /// Get queried target by label
+    fn target(&self, label: &str) -> Result<&BazelTarget> {
+        self.query
+            .get(label)
+            .ok_or_else(|| Error::Lookup(label.into(), "target".into()))
+    }
+
+    /// Get `bazel info` value by key
+    async fn info(&self, key: &str) -> Result<String> {
+        let output = Command::new(&self.bazel)
+            .args(["info", key])
+            .current_dir(self.root())
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Build(format!("Getting bazel {key} failed {stderr}")));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+/// Get trimmed stdout of a successful command
+async fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Read bundle identifier of the given app bundle
+async fn bundle_identifier(app: &Path) -> Result<String> {
+    let info = app.join("Info.plist").display().to_string();
+    command_output(
+        "plutil",
+        &["-extract", "CFBundleIdentifier", "raw", "-o", "-", &info],
+    )
+    .await
+    .ok_or_else(|| Error::Run(format!("Reading bundle identifier of {app:?} failed")))
+}
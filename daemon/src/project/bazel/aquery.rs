@@ -0,0 +1,253 @@
+//! Compiler invocations read from `bazel aquery --output=jsonproto`.
+use crate::{Error, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Mnemonics of swift, objc and c compile actions
+const COMPILE_MNEMONICS: &str = "SwiftCompile|ObjcCompile|CppCompile";
+
+/// Query expression matching compile actions of given targets and their dependencies
+pub fn compile_actions_query(targets: &str) -> String {
+    format!(r#"mnemonic("{COMPILE_MNEMONICS}", deps({targets}))"#)
+}
+
+/// Query expression matching compile actions of given targets only
+pub fn compile_actions_query_shallow(targets: &str) -> String {
+    format!(r#"mnemonic("{COMPILE_MNEMONICS}", {targets})"#)
+}
+
+/// Prefix of rules_swift worker flags, which swiftc doesn't understand
+const WRAPPED_SWIFT_FLAG: &str = "-Xwrapped-swift=";
+
+/// Placeholder replaced by bazel apple wrappers with the developer directory
+const DEVELOPER_DIR: &str = "__BAZEL_XCODE_DEVELOPER_DIR__";
+
+/// Placeholder replaced by bazel apple wrappers with the sdk path
+const SDKROOT: &str = "__BAZEL_XCODE_SDKROOT__";
+
+#[derive(Debug, Deserialize)]
+struct ActionGraph {
+    #[serde(default)]
+    actions: Vec<Action>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Action {
+    mnemonic: String,
+    #[serde(default)]
+    arguments: Vec<String>,
+    /// Param files content, included with `--include_param_files`
+    #[serde(default)]
+    param_files: Vec<ParamFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ParamFile {
+    exec_path: String,
+    #[serde(default)]
+    arguments: Vec<String>,
+}
+
+impl Action {
+    /// Get arguments with `@<param file>` arguments replaced by the param files arguments
+    fn expanded_arguments(self) -> Vec<String> {
+        let Self {
+            arguments,
+            param_files,
+            ..
+        } = self;
+
+        arguments
+            .into_iter()
+            .flat_map(|arg| {
+                let param_file = arg
+                    .strip_prefix('@')
+                    .and_then(|path| param_files.iter().find(|file| file.exec_path == path));
+                match param_file {
+                    Some(file) => file.arguments.clone(),
+                    None => vec![arg],
+                }
+            })
+            .collect()
+    }
+}
+
+/// Get compile actions arguments, starting with swiftc or clang and skipping bazel wrappers.
+///
+/// Param files are expanded, rules_swift worker flags are dropped and apple_support
+/// `wrapped_clang` is replaced with clang.
+pub fn compile_arguments(content: &[u8]) -> Result<Vec<Vec<String>>> {
+    let graph: ActionGraph =
+        serde_json::from_slice(content).map_err(|e| Error::DefinitionParsing(e.to_string()))?;
+
+    Ok(graph
+        .actions
+        .into_iter()
+        .filter(|action| {
+            matches!(
+                action.mnemonic.as_str(),
+                "SwiftCompile" | "ObjcCompile" | "CppCompile"
+            )
+        })
+        .filter_map(|action| {
+            let arguments = action.expanded_arguments();
+            let start = arguments.iter().position(|arg| {
+                let program = Path::new(arg).file_name().and_then(|name| name.to_str());
+                matches!(
+                    program,
+                    Some("swiftc" | "clang" | "wrapped_clang" | "wrapped_clang_pp")
+                )
+            })?;
+
+            let mut arguments = arguments[start..]
+                .iter()
+                .filter(|arg| !arg.starts_with(WRAPPED_SWIFT_FLAG))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let program = Path::new(&arguments[0])
+                .file_name()
+                .and_then(|n| n.to_str());
+            match program {
+                Some("wrapped_clang") => arguments[0] = "clang".into(),
+                Some("wrapped_clang_pp") => arguments[0] = "clang++".into(),
+                _ => {}
+            }
+
+            Some(arguments)
+        })
+        .collect())
+}
+
+/// Get sdk name of compile arguments from their `-target` triple, e.g. `iphonesimulator`
+pub fn sdk_name(args: &[String]) -> &'static str {
+    let triple = args
+        .iter()
+        .position(|arg| arg == "-target")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+        .unwrap_or_default();
+    let is_simulator = triple.ends_with("-simulator");
+
+    match triple.split('-').nth(2).unwrap_or_default() {
+        os if os.starts_with("ios") && is_simulator => "iphonesimulator",
+        os if os.starts_with("ios") => "iphoneos",
+        os if os.starts_with("tvos") && is_simulator => "appletvsimulator",
+        os if os.starts_with("tvos") => "appletvos",
+        os if os.starts_with("watchos") && is_simulator => "watchsimulator",
+        os if os.starts_with("watchos") => "watchos",
+        _ => "macosx",
+    }
+}
+
+/// Replace bazel xcode placeholders with the developer directory and sdk paths
+pub fn substitute(
+    args: Vec<String>,
+    developer_dir: &str,
+    sdk_paths: &HashMap<&str, String>,
+) -> Vec<String> {
+    let sdk_path = sdk_paths.get(sdk_name(&args)).cloned().unwrap_or_default();
+
+    args.into_iter()
+        .map(|arg| {
+            arg.replace(DEVELOPER_DIR, developer_dir)
+                .replace(SDKROOT, &sdk_path)
+        })
+        .collect()
+}
+
+#[test]
+fn test_compile_arguments() {
+    let content = serde_json::json!({
+        "actions": [
+            {
+                "mnemonic": "SwiftCompile",
+                "arguments": [
+                    "bazel-out/darwin_arm64-opt-exec/bin/external/build_bazel_rules_swift/tools/worker/worker",
+                    "swiftc",
+                    "-target",
+                    "arm64-apple-ios15.0-simulator",
+                    "-sdk",
+                    "__BAZEL_XCODE_SDKROOT__",
+                    "-module-name",
+                    "App",
+                    "App/App.swift"
+                ]
+            },
+            { "mnemonic": "CppCompile", "arguments": ["/bin/true"] }
+        ]
+    });
+
+    let actions = compile_arguments(content.to_string().as_bytes()).unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0][0], "swiftc");
+    assert_eq!(sdk_name(&actions[0]), "iphonesimulator");
+
+    let sdk_paths = HashMap::from([("iphonesimulator", "/sdk".to_string())]);
+    let args = substitute(actions[0].clone(), "/dev", &sdk_paths);
+    assert_eq!(args[4], "/sdk");
+}
+
+#[test]
+fn test_compile_arguments_param_files() {
+    let params = "bazel-out/ios_sim_arm64-fastbuild/bin/App/App.swiftmodule-0.params";
+    let content = serde_json::json!({
+        "actions": [
+            {
+                "mnemonic": "SwiftCompile",
+                "arguments": [
+                    "bazel-out/darwin_arm64-opt-exec/bin/external/build_bazel_rules_swift/tools/worker/worker",
+                    "swiftc",
+                    format!("@{params}")
+                ],
+                "paramFiles": [
+                    {
+                        "execPath": params,
+                        "arguments": [
+                            "-Xwrapped-swift=-debug-prefix-pwd-is-dot",
+                            "-target",
+                            "arm64-apple-ios15.0-simulator",
+                            "-module-name",
+                            "App",
+                            "App/App.swift"
+                        ]
+                    }
+                ]
+            },
+            {
+                "mnemonic": "ObjcCompile",
+                "arguments": [
+                    "external/local_config_apple_cc/wrapped_clang",
+                    "-arch",
+                    "arm64",
+                    "-c",
+                    "Lib/Lib.m"
+                ]
+            }
+        ]
+    });
+
+    let actions = compile_arguments(content.to_string().as_bytes()).unwrap();
+    assert_eq!(
+        actions[0],
+        [
+            "swiftc",
+            "-target",
+            "arm64-apple-ios15.0-simulator",
+            "-module-name",
+            "App",
+            "App/App.swift"
+        ]
+    );
+    assert_eq!(actions[1][0], "clang");
+
+    let command = crate::project::swift::CompileCommand::from_args(
+        actions[0].clone(),
+        Path::new("/execroot"),
+    )
+    .unwrap();
+    assert_eq!(command.name, "App");
+}
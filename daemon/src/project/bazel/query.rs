@@ -0,0 +1,91 @@
+//! Bazel targets read from `bazel query --output=label_kind`.
+use serde::Serialize;
+use xcodeproj::pbxproj::PBXTargetPlatform;
+
+/// Query expression matching targets xbase can build, run or test
+pub const TARGETS_QUERY: &str = r#"kind("(swift|objc|cc)_library|swift_binary|swift_test|cc_binary|macos_command_line_application|(ios|macos|tvos|watchos)_(application|unit_test|ui_test)", //...)"#;
+
+/// Bazel target kind
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BazelTargetKind {
+    /// Command line binaries, run directly
+    Executable,
+    /// Application bundles, run on a simulator or, for macOS, directly
+    Application,
+    Library,
+    Test,
+}
+
+/// Bazel target as listed by `bazel query`
+#[derive(Debug, Clone)]
+pub struct BazelTarget {
+    pub label: String,
+    pub rule: String,
+    pub kind: BazelTargetKind,
+    pub platform: PBXTargetPlatform,
+}
+
+impl BazelTarget {
+    /// Parse a `bazel query --output=label_kind` line, e.g. `swift_library rule //Foo:Foo`
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        let rule = parts.next()?.to_string();
+        if parts.next()? != "rule" {
+            return None;
+        }
+        let label = parts.next()?.to_string();
+
+        let kind = if rule.ends_with("_test") {
+            BazelTargetKind::Test
+        } else if rule.ends_with("_application") {
+            BazelTargetKind::Application
+        } else if matches!(
+            rule.as_str(),
+            "swift_binary" | "cc_binary" | "macos_command_line_application"
+        ) {
+            BazelTargetKind::Executable
+        } else {
+            BazelTargetKind::Library
+        };
+
+        let platform = match rule.split('_').next() {
+            Some("ios") => PBXTargetPlatform::IOS,
+            Some("tvos") => PBXTargetPlatform::TvOS,
+            Some("watchos") => PBXTargetPlatform::WatchOS,
+            _ => PBXTargetPlatform::MacOS,
+        };
+
+        Some(Self {
+            label,
+            rule,
+            kind,
+            platform,
+        })
+    }
+
+    /// Whether the target is run on a simulator rather than directly
+    pub fn requires_simulator(&self) -> bool {
+        self.kind == BazelTargetKind::Application
+            && !matches!(self.platform, PBXTargetPlatform::MacOS)
+    }
+}
+
+#[test]
+fn test_parse_bazel_target() {
+    let target = BazelTarget::parse("ios_application rule //App:App").unwrap();
+    assert_eq!(target.label, "//App:App");
+    assert_eq!(target.kind, BazelTargetKind::Application);
+    assert!(matches!(target.platform, PBXTargetPlatform::IOS));
+    assert!(target.requires_simulator());
+
+    let target = BazelTarget::parse("swift_binary rule //Tools:lint").unwrap();
+    assert_eq!(target.kind, BazelTargetKind::Executable);
+    assert!(matches!(target.platform, PBXTargetPlatform::MacOS));
+
+    let target = BazelTarget::parse("ios_unit_test rule //App:AppTests").unwrap();
+    assert_eq!(target.kind, BazelTargetKind::Test);
+
+    assert!(BazelTarget::parse("Loading: 0 packages loaded").is_none());
+}
@@ -0,0 +1,91 @@
+//! Discovery of projects nested in a registered root.
+use super::{generate_watchignore, WORKSPACE_FILES};
+use std::path::{Path, PathBuf};
+use wax::Pattern;
+use xbase_proto::DISCOVERY_DEPTH;
+
+/// Files marking a directory as project root
+const DEFINITION_FILES: [&str; 3] = ["project.yml", "Project.swift", "Package.swift"];
+
+/// Whether the given directory directly contains a project definition or a xcodeproj
+pub fn is_project_root(dir: &Path) -> bool {
+    let mut definitions = DEFINITION_FILES.iter().chain(WORKSPACE_FILES.iter());
+    if definitions.any(|name| dir.join(name).exists()) {
+        return true;
+    }
+
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|entry| is_xcode_bundle(&entry.path()))
+        })
+        .unwrap_or_default()
+}
+
+/// Get the given root and every nested project root, skipping root watchignore patterns
+pub async fn discover_roots(root: &Path) -> Vec<PathBuf> {
+    let watchignore = generate_watchignore(root).await;
+    let walk_root = root.to_path_buf();
+
+    // NOTE: The tree is walked with blocking filesystem calls
+    match tokio::task::spawn_blocking(move || walk_roots(&walk_root, &watchignore)).await {
+        Ok(roots) => roots,
+        Err(err) => {
+            log::error!("Fail to discover nested projects: {err}");
+            vec![root.to_path_buf()]
+        }
+    }
+}
+
+fn walk_roots(root: &Path, watchignore: &[String]) -> Vec<PathBuf> {
+    let patterns = watchignore.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+    let ignore = match wax::any::<wax::Glob, _>(patterns) {
+        Ok(ignore) => ignore,
+        Err(err) => {
+            log::error!("Fail to parse watchignore patterns: {err}");
+            return vec![root.to_path_buf()];
+        }
+    };
+
+    let mut roots = vec![];
+    let mut dirs = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = dirs.pop() {
+        if is_project_root(&dir) {
+            roots.push(dir.clone());
+        }
+
+        if depth == DISCOVERY_DEPTH {
+            continue;
+        }
+
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::debug!("Fail to read {dir:?}: {err}");
+                continue;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or_default();
+
+            // NOTE: xcodeproj and xcworkspace are project files rather than nested projects
+            if is_dir && !is_xcode_bundle(&path) && !ignore.is_match(&*path.to_string_lossy()) {
+                dirs.push((path, depth + 1));
+            }
+        }
+    }
+
+    roots.sort();
+    roots
+}
+
+fn is_xcode_bundle(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("xcodeproj" | "xcworkspace")
+    )
+}
@@ -1,20 +1,31 @@
 mod barebone;
+mod bazel;
+mod discovery;
+mod scheme;
 mod swift;
 mod tuist;
+mod workspace;
 mod xcodegen;
 
+use crate::{build::BuildEvent, BuildStream, OutputStream, Result};
 use crate::{device::*, run::*, util::*, watch::*};
-use crate::{Result, StringStream};
 use anyhow::Context;
 use async_stream::stream;
 use barebone::BareboneProject;
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
+use process_stream::Process;
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use xbase_proto::{BuildSettings, Client};
-use xclog::{XCBuildSettings, XCLogger};
+use xclog::{XCBuildSettings, XCCompileCommand, XCLogger};
 use xcodeproj::pbxproj::PBXTargetPlatform;
-use {swift::*, tuist::*, xcodegen::*};
+use {bazel::*, swift::*, tuist::*, xcodegen::*};
+
+pub use discovery::*;
+pub use scheme::*;
+pub use swift::Toolchain;
+pub use workspace::*;
 
 /// Project Data
 pub trait ProjectData: std::fmt::Debug {
@@ -24,6 +35,55 @@ pub trait ProjectData: std::fmt::Debug {
     fn name(&self) -> &str;
     /// Project targets
     fn targets(&self) -> &HashMap<String, PBXTargetPlatform>;
+    /// Workspace to build with
+    fn workspace(&self) -> Option<&PathBuf> {
+        None
+    }
+    /// Xcode project defining the given target
+    fn target_xcodeproj(&self, _target: &str) -> Option<&PathBuf> {
+        None
+    }
+    /// Xcode projects defining a target named like one of another project, by target name.
+    /// Such targets are compiled but can't be built by name.
+    fn shadowed_targets(&self) -> HashMap<String, Vec<PathBuf>> {
+        HashMap::new()
+    }
+    /// Project schemes by name
+    fn schemes(&self) -> &HashMap<String, Scheme>;
+    /// Project scheme by name
+    fn scheme(&self, name: &str) -> Option<&Scheme> {
+        self.schemes().get(name)
+    }
+    /// Scheme named after the given target, or otherwise the first one building or testing it
+    fn target_scheme(&self, target: &str) -> Option<&Scheme> {
+        self.scheme(target).or_else(|| {
+            let mut schemes = self.schemes().values().collect::<Vec<_>>();
+            schemes.sort_by(|a, b| a.name.cmp(&b.name));
+            schemes.into_iter().find(|scheme| {
+                scheme
+                    .build_targets
+                    .iter()
+                    .chain(&scheme.test_targets)
+                    .any(|t| t == target)
+            })
+        })
+    }
+    /// Name of the scheme to build each target with, published to clients with project state.
+    /// Empty for projects not built with schemes.
+    fn target_schemes(&self) -> HashMap<String, String> {
+        self.targets()
+            .keys()
+            .filter_map(|target| Some((target.clone(), self.target_scheme(target)?.name.clone())))
+            .collect()
+    }
+    /// Scheme named after the project, or otherwise the one building the most targets
+    fn default_scheme(&self) -> Option<&Scheme> {
+        self.scheme(self.name()).or_else(|| {
+            self.schemes()
+                .values()
+                .max_by_key(|scheme| (scheme.build_targets.len(), Reverse(&scheme.name)))
+        })
+    }
     /// Project clients
     fn clients(&self) -> &Vec<i32>;
     /// Get mut clients
@@ -51,7 +111,27 @@ pub trait ProjectBuild: ProjectData {
         &self,
         cfg: &BuildSettings,
         device: Option<&Device>,
-    ) -> Result<(StringStream, Vec<String>)> {
+    ) -> Result<(BuildStream, Vec<String>)> {
+        let args = self.build_arguments(cfg, device)?;
+
+        log::trace!("building with [{}]", args.join(" "));
+
+        let mut xclogger = XCLogger::new(self.root(), &args)?;
+        let stream = stream! {
+            while let Some(output) =  xclogger.next().await {
+                if output.is_result() && output.starts_with("[Exit]") {
+                    yield BuildEvent::from_exit(output.strip_prefix("[Exit]").unwrap_or_default())
+                } else {
+                    yield BuildEvent::from_line(output.to_string())
+                }
+            }
+        };
+
+        Ok((stream.boxed(), args))
+    }
+
+    /// Get xcodebuild arguments required to build using BuildSettings and optionally a device
+    fn build_arguments(&self, cfg: &BuildSettings, device: Option<&Device>) -> Result<Vec<String>> {
         let mut args = cfg.to_args();
 
         args.insert(0, "build".to_string());
@@ -66,34 +146,39 @@ pub trait ProjectBuild: ProjectData {
         args.push("-allowProvisioningUpdates".into());
 
         let name = self.name().to_owned();
-        let xcworkspace = format!("{}.xcworkspace", &name);
-        if self.root().join(&xcworkspace).exists() {
-            args.iter_mut().for_each(|arg| {
-                if arg == "-target" {
-                    *arg = "-scheme".into()
-                }
-            });
-            args.extend_from_slice(&["-workspace".into(), xcworkspace]);
-        } else {
-            args.extend_from_slice(&["-project".into(), format!("{}.xcodeproj", name)]);
+        let xcworkspace = match self.workspace() {
+            Some(path) => Some(path.display().to_string()),
+            None => Some(format!("{}.xcworkspace", &name))
+                .filter(|xcworkspace| self.root().join(xcworkspace).exists()),
+        };
+        let has_workspace = xcworkspace.is_some();
+
+        // NOTE: Schemes decide what targets get built, and workspaces can only be built with one
+        if cfg.scheme.is_some() || has_workspace {
+            if let Some(idx) = args.iter().position(|arg| arg == "-target") {
+                args.drain(idx..=idx + 1);
+            }
         }
 
-        log::trace!("building with [{}]", args.join(" "));
+        if cfg.scheme.is_none() && has_workspace {
+            let scheme = match self.target_scheme(&cfg.target) {
+                Some(scheme) => scheme.name.clone(),
+                None => cfg.target.clone(),
+            };
+            args.extend_from_slice(&["-scheme".into(), scheme]);
+        }
 
-        let mut xclogger = XCLogger::new(self.root(), &args)?;
-        let stream = stream! {
-            while let Some(output) =  xclogger.next().await {
-                if output.is_result() && output.starts_with("[Exit]") {
-                    if !output.strip_prefix("[Exit] ").map(|s| s == "0").unwrap_or_default() {
-                        yield String::from("FAILED")
-                    }
-                } else {
-                    yield output.to_string()
-                }
-            }
-        };
+        if let Some(xcworkspace) = xcworkspace {
+            args.extend_from_slice(&["-workspace".into(), xcworkspace]);
+        } else {
+            let xcodeproj = match self.target_xcodeproj(&cfg.target) {
+                Some(path) => path.display().to_string(),
+                None => format!("{}.xcodeproj", name),
+            };
+            args.extend_from_slice(&["-project".into(), xcodeproj]);
+        }
 
-        Ok((stream.boxed(), args))
+        Ok(args)
     }
 
     /// Get build cache root
@@ -110,15 +195,67 @@ pub trait ProjectRun: ProjectData + ProjectBuild {
         &self,
         cfg: &BuildSettings,
         device: Option<&Device>,
-    ) -> Result<(Box<dyn Runner + Send + Sync>, StringStream, Vec<String>)> {
+    ) -> Result<(Box<dyn Runner + Send + Sync>, BuildStream, Vec<String>)> {
         let (build_stream, args) = self.build(cfg, device)?;
-        let info = XCBuildSettings::new_sync(self.root(), &args)?;
-        let runner: Box<dyn Runner + Send + Sync> = match device {
-            Some(device) => Box::new(SimulatorRunner::new(device.clone(), &info)),
-            None => Box::new(BinRunner::from_build_info(&info)),
-        };
+        let (root, device, build_args) = (self.root().clone(), device.cloned(), args.clone());
+
+        // NOTE: Build settings are only read once the build succeeded
+        let runner = LazyRunner::new(move || {
+            let (root, device, args) = (root.clone(), device.clone(), build_args.clone());
+            async move {
+                let info = XCBuildSettings::new(&root, &args).await?;
+                let runner: Box<dyn Runner + Send + Sync> = match device {
+                    Some(device) => Box::new(SimulatorRunner::new(device, &info)),
+                    None => Box::new(BinRunner::from_build_info(&info)),
+                };
+                Ok(runner)
+            }
+            .boxed()
+        });
+
+        Ok((Box::new(runner), build_stream, args))
+    }
+}
+
+#[async_trait::async_trait]
+pub trait ProjectTest: ProjectData + ProjectBuild {
+    /// Test Project using BuildSettings and optionally a device
+    fn test(
+        &self,
+        cfg: &BuildSettings,
+        device: Option<&Device>,
+    ) -> Result<(BuildStream, Vec<String>)> {
+        let mut args = self.build_arguments(cfg, device)?;
+
+        args.iter_mut().for_each(|arg| {
+            if arg == "build" {
+                *arg = "test".into()
+            }
+        });
+
+        // NOTE: xcodebuild test action only works with schemes
+        if let Some(idx) = args.iter().position(|arg| arg == "-target") {
+            args[idx] = "-scheme".into();
+            args[idx + 1] = match self.target_scheme(&cfg.target) {
+                Some(scheme) => scheme.name.clone(),
+                None => cfg.target.clone(),
+            };
+        }
+
+        if let Some(device) = device {
+            args.extend(["-destination".into(), format!("id={}", device.udid)]);
+        }
+
+        log::trace!("testing with [{}]", args.join(" "));
+
+        let mut process = Process::new("/usr/bin/xcodebuild");
+
+        process.args(&args);
+        process.current_dir(self.root());
 
-        Ok((runner, build_stream, args))
+        let stream = Box::pin(process.spawn_and_stream()?);
+
+        Ok((process_build_events(stream), args))
     }
 }
 
@@ -127,6 +264,162 @@ pub trait ProjectCompile: ProjectData {
     /// Generate compile database in project root
     async fn update_compile_database(&self) -> Result<()>;
 
+    /// Regenerate compile commands of targets affected by file events and merge them into
+    /// existing compile database, keeping build cache. Fallback to full regeneration when no
+    /// affected target can be resolved.
+    async fn update_compile_database_incremental(&self, events: &[&Event]) -> Result<()> {
+        use crate::compile::{affected_modules, is_target_module, merge_compile_commands};
+        use xclog::XCCompilationDatabase as CC;
+
+        let root = self.root();
+        let compile_path = root.join(".compile");
+        let existing = match CC::try_from_filepath(&compile_path) {
+            Ok(compile_db) => compile_db.to_vec(),
+            Err(_) => return self.update_compile_database().await,
+        };
+
+        let modules = events
+            .iter()
+            .flat_map(|event| affected_modules(&existing, event.path()))
+            .collect::<std::collections::HashSet<_>>();
+        let targets = self
+            .targets()
+            .keys()
+            .filter(|target| modules.iter().any(|m| is_target_module(target, m)))
+            .collect::<Vec<_>>();
+
+        if targets.is_empty() {
+            log::info!("No affected targets found, regenerating compile database");
+            return self.update_compile_database().await;
+        }
+
+        let mut args = self
+            .compile_arguments()
+            .into_iter()
+            .filter(|arg| arg != "clean")
+            .collect::<Vec<_>>();
+
+        args.push(format!("SYMROOT={}", fs::get_build_cache_dir(root)?));
+
+        // NOTE: Targets are built with the project defining them
+        let mut projects = std::collections::BTreeMap::<String, Vec<&String>>::new();
+        for target in targets {
+            let xcodeproj = match self.target_xcodeproj(target) {
+                Some(path) => path.display().to_string(),
+                None => format!("{}.xcodeproj", self.name()),
+            };
+            projects.entry(xcodeproj).or_default().push(target);
+        }
+
+        let mut updates = vec![];
+        for (xcodeproj, targets) in projects {
+            let mut args = args.clone();
+            args.extend_from_slice(&["-project".into(), xcodeproj]);
+            for target in targets {
+                args.extend_from_slice(&["-target".into(), target.clone()]);
+            }
+
+            log::info!("xcodebuild {}", args.join(" "));
+
+            updates.extend(CC::generate(&root, &args).await?.to_vec());
+        }
+
+        let compile_commands = merge_compile_commands(existing, updates);
+        let json = serde_json::to_vec_pretty(&compile_commands)?;
+
+        tokio::fs::write(compile_path, &json).await?;
+        log::debug!("[{}] compile database updated", self.name());
+
+        Ok(())
+    }
+
+    /// Get xcodebuild arguments to compile every target. With a workspace, schemes are added,
+    /// starting with the default one, until every target is built. Targets no scheme builds are
+    /// built with their owning project.
+    fn compile_containers(&self) -> Vec<Vec<String>> {
+        use std::collections::{BTreeMap, HashSet};
+
+        let mut remaining = self.targets().keys().collect::<HashSet<_>>();
+        let mut containers = vec![];
+
+        if let Some(xcworkspace) = self.workspace() {
+            let mut schemes = self.schemes().values().collect::<Vec<_>>();
+            schemes.sort_by(|a, b| a.name.cmp(&b.name));
+            if let Some(default) = self.default_scheme() {
+                schemes.retain(|scheme| scheme.name != default.name);
+                schemes.insert(0, default);
+            }
+
+            for scheme in schemes {
+                let builds_remaining = scheme
+                    .build_targets
+                    .iter()
+                    .filter(|target| remaining.remove(target))
+                    .count()
+                    > 0;
+
+                if builds_remaining {
+                    containers.push(vec![
+                        "-workspace".into(),
+                        xcworkspace.display().to_string(),
+                        "-scheme".into(),
+                        scheme.name.clone(),
+                    ]);
+                }
+            }
+        }
+
+        let mut projects = BTreeMap::<String, Vec<String>>::new();
+        for target in remaining {
+            if let Some(xcodeproj) = self.target_xcodeproj(target) {
+                let targets = projects.entry(xcodeproj.display().to_string()).or_default();
+                targets.extend(["-target".into(), target.clone()]);
+            }
+        }
+        for (target, xcodeprojs) in self.shadowed_targets() {
+            for xcodeproj in xcodeprojs {
+                let targets = projects.entry(xcodeproj.display().to_string()).or_default();
+                targets.extend(["-target".into(), target.clone()]);
+            }
+        }
+
+        for (xcodeproj, targets) in projects {
+            containers.push(
+                ["-project".into(), xcodeproj]
+                    .into_iter()
+                    .chain(targets)
+                    .collect(),
+            );
+        }
+
+        // NOTE: Let xcodebuild find the project in root when owners are unknown
+        if containers.is_empty() {
+            containers.push(vec![]);
+        }
+
+        containers
+    }
+
+    /// Compile every target with given xcodebuild arguments, see `compile_containers`, and merge
+    /// their compile commands
+    async fn generate_compile_commands(&self, args: Vec<String>) -> Result<Vec<XCCompileCommand>> {
+        use crate::compile::merge_compile_commands;
+        use xclog::XCCompilationDatabase as CC;
+
+        let mut compile_commands = vec![];
+        for container in self.compile_containers() {
+            let mut args = args.clone();
+            args.extend(container);
+
+            log::info!("xcodebuild {}", args.join(" "));
+
+            let updates = CC::generate(self.root(), &args).await?.to_vec();
+            compile_commands = merge_compile_commands(compile_commands, updates);
+        }
+
+        Ok(compile_commands)
+    }
+
     /// Get compile arguments
     fn compile_arguments(&self) -> Vec<String> {
         vec![
@@ -151,6 +444,28 @@ pub trait ProjectGenerate: ProjectData {
     fn should_generate(&self, _event: &Event) -> bool {
         false
     }
+
+    /// Directories in which adding, removing or renaming files changes the project definition,
+    /// computed from the project definition on generate.
+    fn source_roots(&self) -> &[PathBuf] {
+        &[]
+    }
+
+    /// Recompute source roots, called after the compile database is updated
+    async fn update_source_roots(&mut self) {}
+
+    /// Whether the event adds, removes or renames a file in one of the source roots. Matches
+    /// any path while source roots are unknown.
+    fn is_source_tree_update(&self, event: &Event) -> bool {
+        let is_tree_update =
+            event.is_create_event() || event.is_remove_event() || event.is_rename_event();
+        if !is_tree_update {
+            return false;
+        }
+
+        let roots = self.source_roots();
+        roots.is_empty() || roots.iter().any(|root| event.path().starts_with(root))
+    }
     /// Generate xcodeproj
     async fn generate(&mut self) -> Result<()>;
 }
@@ -161,6 +476,7 @@ pub trait Project:
     ProjectData
     + ProjectBuild
     + ProjectRun
+    + ProjectTest
     + ProjectCompile
     + ProjectGenerate
     + Sync
@@ -182,6 +498,9 @@ pub async fn project(client: &Client) -> Result<Box<dyn Project + Send + Sync>>
         Ok(Box::new(XCodeGenProject::new(client).await?))
     } else if root.join("Project.swift").exists() {
         Ok(Box::new(TuistProject::new(client).await?))
+    } else if WORKSPACE_FILES.iter().any(|name| root.join(name).exists()) {
+        // NOTE: Checked before Package.swift, rules_swift_package_manager keeps one at the root
+        Ok(Box::new(BazelProject::new(client).await?))
     } else if root.join("Package.swift").exists() {
         Ok(Box::new(SwiftProject::new(client).await?))
     } else {
@@ -189,6 +508,22 @@ pub async fn project(client: &Client) -> Result<Box<dyn Project + Send + Sync>>
     }
 }
 
+/// Whether the given root is a swift package rather than a bazel workspace with a root package
+pub fn is_swift_package(root: &Path) -> bool {
+    root.join("Package.swift").exists()
+        && !WORKSPACE_FILES.iter().any(|name| root.join(name).exists())
+}
+
+/// Convert process output to build events.
+fn process_build_events(stream: OutputStream) -> BuildStream {
+    stream
+        .map(|output| {
+            log::trace!("{output}");
+            BuildEvent::from(output)
+        })
+        .boxed()
+}
+
 async fn generate_watchignore<P: AsRef<Path>>(root: P) -> Vec<String> {
     let mut default = vec![
         "**/.git/**".into(),
@@ -211,3 +546,28 @@ async fn generate_watchignore<P: AsRef<Path>>(root: P) -> Vec<String> {
 
     default
 }
+
+/// Get source roots of xcodeproj projects: directories of their groups under root, and
+/// directories of files in an existing compile database.
+async fn xcodeproj_source_roots(root: &Path, xcodeprojs: &XCodeProjects) -> Vec<PathBuf> {
+    use crate::compile::compiled_source_roots;
+    use xclog::XCCompilationDatabase as CC;
+
+    // NOTE: A group at root would match any path
+    let mut roots = xcodeprojs
+        .group_dirs()
+        .into_iter()
+        .filter(|dir| dir.starts_with(root) && dir != root)
+        .collect::<Vec<_>>();
+
+    let compile_path = root.join(".compile");
+    let compile_db =
+        tokio::task::spawn_blocking(move || CC::try_from_filepath(&compile_path)).await;
+    if let Ok(Ok(compile_db)) = compile_db {
+        roots.extend(compiled_source_roots(&compile_db.to_vec(), root));
+    }
+
+    roots.sort();
+    roots.dedup();
+    roots
+}
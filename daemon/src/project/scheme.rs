@@ -0,0 +1,214 @@
+//! Xcode scheme (`.xcscheme`) parsing.
+use crate::util::xml::tags;
+use crate::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use xbase_proto::LaunchOptions;
+
+/// Xcode scheme build, launch and test actions
+#[derive(Debug, Default, Clone, Serialize, PartialEq, Eq)]
+pub struct Scheme {
+    pub name: String,
+    /// BuildAction targets
+    pub build_targets: Vec<String>,
+    /// LaunchAction configuration, target, arguments and environment variables
+    pub launch: SchemeLaunch,
+    /// TestAction testable targets that aren't skipped
+    pub test_targets: Vec<String>,
+    /// TestAction configuration
+    pub test_configuration: Option<String>,
+}
+
+/// Xcode scheme LaunchAction
+#[derive(Debug, Default, Clone, Serialize, PartialEq, Eq)]
+pub struct SchemeLaunch {
+    pub configuration: Option<String>,
+    pub target: Option<String>,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+}
+
+impl Scheme {
+    /// Read schemes defined in given xcodeproj and xcworkspace paths, falling back to the ones
+    /// xcodebuild automatically creates for each target when none is defined
+    pub fn discover<'a>(
+        containers: &[PathBuf],
+        targets: impl IntoIterator<Item = &'a String>,
+    ) -> HashMap<String, Scheme> {
+        let schemes = Self::load_all(containers);
+        if !schemes.is_empty() {
+            return schemes;
+        }
+
+        targets
+            .into_iter()
+            .map(|target| (target.clone(), Self::for_target(target)))
+            .collect()
+    }
+
+    /// Scheme building and launching a single target
+    pub fn for_target(target: &str) -> Self {
+        Self {
+            name: target.to_string(),
+            build_targets: vec![target.to_string()],
+            launch: SchemeLaunch {
+                target: Some(target.to_string()),
+                ..SchemeLaunch::default()
+            },
+            ..Self::default()
+        }
+    }
+
+    /// Read all shared and user schemes of given xcodeproj and xcworkspace paths
+    pub fn load_all(containers: &[PathBuf]) -> HashMap<String, Scheme> {
+        let mut paths = vec![];
+        for container in containers {
+            match wax::walk("**/*.xcscheme", container) {
+                Ok(walk) => paths.extend(walk.flatten().map(|entry| entry.into_path())),
+                Err(err) => log::error!("Fail to find schemes in {container:?}: {err}"),
+            }
+        }
+
+        paths
+            .into_iter()
+            .filter_map(|path| match Self::from_path(&path) {
+                Ok(scheme) => Some((scheme.name.clone(), scheme)),
+                Err(err) => {
+                    log::error!("Fail to read {path:?}: {err}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Read scheme from a given `.xcscheme` path
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::parse(name, &content))
+    }
+
+    /// Parse scheme xml content
+    pub fn parse(name: &str, content: &str) -> Self {
+        let mut scheme = Self {
+            name: name.to_string(),
+            ..Self::default()
+        };
+        let mut stack: Vec<String> = vec![];
+        let mut skipped_testable = false;
+
+        for tag in tags(content) {
+            if tag.is_closing {
+                stack.pop();
+                continue;
+            }
+
+            let parent = stack.last().map(String::as_str).unwrap_or_default();
+            let in_action = |action: &str| stack.iter().any(|name| name == action);
+            let attr = |key: &str| tag.attributes.get(key).cloned();
+            let is_enabled = attr("isEnabled").map(|v| v == "YES").unwrap_or(true);
+
+            match tag.name.as_str() {
+                "LaunchAction" => scheme.launch.configuration = attr("buildConfiguration"),
+                "TestAction" => scheme.test_configuration = attr("buildConfiguration"),
+                "TestableReference" => skipped_testable = attr("skipped") == Some("YES".into()),
+                "BuildableReference" => {
+                    if let Some(target) = attr("BlueprintName") {
+                        match parent {
+                            "BuildActionEntry" if in_action("BuildAction") => {
+                                scheme.build_targets.push(target)
+                            }
+                            "BuildableProductRunnable" if in_action("LaunchAction") => {
+                                scheme.launch.target = Some(target)
+                            }
+                            "TestableReference" if !skipped_testable => {
+                                scheme.test_targets.push(target)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                "CommandLineArgument" if in_action("LaunchAction") && is_enabled => {
+                    // NOTE: Xcode splits each argument shell-style, e.g. `-name "Demo App"`
+                    if let Some(argument) = attr("argument") {
+                        match shell_words::split(&argument) {
+                            Ok(args) => scheme.launch.args.extend(args),
+                            Err(_) => scheme.launch.args.push(argument),
+                        }
+                    }
+                }
+                "EnvironmentVariable" if in_action("LaunchAction") && is_enabled => {
+                    if let (Some(key), Some(value)) = (attr("key"), attr("value")) {
+                        scheme.launch.env.insert(key, value);
+                    }
+                }
+                _ => {}
+            }
+
+            if !tag.is_self_closing {
+                stack.push(tag.name);
+            }
+        }
+
+        scheme
+    }
+
+    /// Launch options defined in LaunchAction
+    pub fn launch_options(&self) -> LaunchOptions {
+        LaunchOptions {
+            args: self.launch.args.clone(),
+            env: self.launch.env.clone(),
+            cwd: None,
+        }
+    }
+}
+
+#[test]
+fn test_parse_scheme() {
+    let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Scheme LastUpgradeVersion = "1340" version = "1.3">
+   <BuildAction parallelizeBuildables = "YES">
+      <BuildActionEntries>
+         <BuildActionEntry buildForRunning = "YES">
+            <BuildableReference BuildableIdentifier = "primary" BlueprintName = "Demo">
+            </BuildableReference>
+         </BuildActionEntry>
+      </BuildActionEntries>
+   </BuildAction>
+   <TestAction buildConfiguration = "Debug">
+      <Testables>
+         <TestableReference skipped = "NO">
+            <BuildableReference BlueprintName = "DemoTests"/>
+         </TestableReference>
+         <TestableReference skipped = "YES">
+            <BuildableReference BlueprintName = "DemoUITests"/>
+         </TestableReference>
+      </Testables>
+   </TestAction>
+   <LaunchAction buildConfiguration = "Staging">
+      <BuildableProductRunnable runnableDebuggingMode = "0">
+         <BuildableReference BlueprintName = "Demo"/>
+      </BuildableProductRunnable>
+      <CommandLineArguments>
+         <CommandLineArgument argument = "-name &quot;Demo App&quot;" isEnabled = "YES"/>
+         <CommandLineArgument argument = "-disabled" isEnabled = "NO"/>
+      </CommandLineArguments>
+      <EnvironmentVariables>
+         <EnvironmentVariable key = "LOG_LEVEL" value = "debug" isEnabled = "YES"/>
+      </EnvironmentVariables>
+   </LaunchAction>
+</Scheme>"#;
+
+    let scheme = Scheme::parse("Demo", content);
+    assert_eq!(scheme.build_targets, ["Demo"]);
+    assert_eq!(scheme.test_targets, ["DemoTests"]);
+    assert_eq!(scheme.test_configuration.as_deref(), Some("Debug"));
+    assert_eq!(scheme.launch.configuration.as_deref(), Some("Staging"));
+    assert_eq!(scheme.launch.target.as_deref(), Some("Demo"));
+    assert_eq!(scheme.launch.args, ["-name", "Demo App"]);
+    assert_eq!(scheme.launch.env["LOG_LEVEL"], "debug");
+}
@@ -1,22 +1,41 @@
+mod compile;
+mod package;
+mod toolchain;
+
 use super::*;
 use crate::watch::Event;
 use crate::{Error, Result};
 use futures::StreamExt;
-use process_stream::{Process, ProcessItem};
+use process_stream::Process;
 use serde::Serialize;
 use std::{collections::HashMap, path::PathBuf};
 use tokio::process::Command;
 use xbase_proto::Client;
 use xcodeproj::pbxproj::PBXTargetPlatform;
 
+pub use compile::CompileCommand;
+pub use package::*;
+pub use toolchain::*;
+
 #[derive(Debug, Serialize, Default)]
 #[serde(default)]
 pub struct SwiftProject {
     name: String,
     root: PathBuf,
     targets: HashMap<String, PBXTargetPlatform>,
+    tests: Vec<String>,
+    /// Package targets kinds, including tests, plugins and other targets that can't be built
+    kinds: HashMap<String, SwiftTargetKind>,
+    /// Executable products names by target name
+    executables: HashMap<String, String>,
+    /// Schemes Xcode generates for the package
+    schemes: HashMap<String, Scheme>,
+    /// Targets sources directories
+    #[serde(skip)]
+    sources: Vec<PathBuf>,
     clients: Vec<i32>,
     watchignore: Vec<String>,
+    toolchain: Toolchain,
 }
 
 impl ProjectData for SwiftProject {
@@ -32,6 +51,14 @@ impl ProjectData for SwiftProject {
         &self.targets
     }
 
+    fn schemes(&self) -> &HashMap<String, Scheme> {
+        &self.schemes
+    }
+
+    fn target_schemes(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
     fn clients(&self) -> &Vec<i32> {
         &self.clients
     }
@@ -51,74 +78,170 @@ impl ProjectBuild for SwiftProject {
         &self,
         cfg: &BuildSettings,
         _device: Option<&Device>,
-    ) -> Result<(StringStream, Vec<String>)> {
+    ) -> Result<(BuildStream, Vec<String>)> {
         log::info!("Building {}", cfg.target);
 
-        let args = vec!["build", "--target", &cfg.target];
-        let mut process = Process::new("/usr/bin/swift");
+        match self.kinds.get(&cfg.target) {
+            Some(kind) if !kind.is_buildable() => {
+                let msg = format!("{} is a {kind} target and can't be built", cfg.target);
+                Err(Error::Build(msg))
+            }
+            _ => self.swift_build(&["--target", &cfg.target]),
+        }
+    }
+}
 
-        process.args(&args);
-        process.current_dir(self.root());
+#[async_trait::async_trait]
+impl ProjectRun for SwiftProject {
+    fn get_runner(
+        &self,
+        cfg: &BuildSettings,
+        _device: Option<&Device>,
+    ) -> Result<(Box<dyn Runner + Send + Sync>, BuildStream, Vec<String>)> {
+        let target = &cfg.target;
+        match self.kinds.get(target) {
+            Some(SwiftTargetKind::Executable) => {}
+            Some(kind) => {
+                let msg = format!("{target} is a {kind} target, only executables can be run");
+                return Err(Error::Run(msg));
+            }
+            None => return Err(Error::Lookup(target.into(), "target".into())),
+        }
 
-        let mut stream = process.spawn_and_stream()?;
-        let stream = stream! {
-            while let Some(output) =  stream.next().await {
-                if let ProcessItem::Exit(v) = output {
-                    if !v.eq("0") {
-                        yield String::from("FAILED")
-                    }
-                } else {
-                    log::trace!("{output}");
-                    yield output.to_string()
+        // NOTE: Building a target doesn't link its executable product
+        let product = self.executable_name(target);
+        let (build_stream, args) = self.swift_build(&["--product", product])?;
+
+        let (swift, root, product) = (
+            self.toolchain.path.clone(),
+            self.root().clone(),
+            product.to_string(),
+        );
+
+        // NOTE: Bin path is only resolved once the build succeeded
+        let runner = LazyRunner::new(move || {
+            let (swift, root, product) = (swift.clone(), root.clone(), product.clone());
+            async move {
+                let output = Command::new(&swift)
+                    .args(["build", "--show-bin-path"])
+                    .current_dir(&root)
+                    .stdin(std::process::Stdio::null())
+                    .output()
+                    .await?;
+
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    return Err(Error::Run(format!(
+                        "Getting target bin path failed {stderr}"
+                    )));
                 }
 
+                let output = String::from_utf8_lossy(&output.stdout);
+                let bin_path = PathBuf::from(output.trim()).join(&product);
+
+                log::info!("Running {product:?} via {bin_path:?}");
+
+                let runner: Box<dyn Runner + Send + Sync> =
+                    Box::new(BinRunner::from_path(&bin_path));
+                Ok(runner)
             }
-        };
-        Ok((stream.boxed(), vec![]))
+            .boxed()
+        });
+
+        Ok((Box::new(runner), build_stream, args))
     }
 }
 
 #[async_trait::async_trait]
-impl ProjectRun for SwiftProject {
-    fn get_runner(
+impl ProjectTest for SwiftProject {
+    fn test(
         &self,
         cfg: &BuildSettings,
         _device: Option<&Device>,
-    ) -> Result<(Box<dyn Runner + Send + Sync>, StringStream, Vec<String>)> {
-        let (build_stream, args) = self.build(cfg, None)?;
+    ) -> Result<(BuildStream, Vec<String>)> {
+        log::info!("Testing {}", cfg.target);
 
-        let output = std::process::Command::new("/usr/bin/swift")
-            .args(["build", "--show-bin-path"])
-            .current_dir(self.root())
-            .output()?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8(output.stderr).unwrap();
-            return Err(Error::Run(format!(
-                "Getting target bin path failed {stderr}"
-            )));
+        if let Some(kind) = self.kinds.get(&cfg.target) {
+            if kind != &SwiftTargetKind::Test {
+                let msg = format!("{} is a {kind} target, not a test target", cfg.target);
+                return Err(Error::Build(msg));
+            }
         }
 
-        // WARN: THIS MIGHT FAIL BECAUSE BUILD IS NOT YET RAN
-        let output = String::from_utf8(output.stdout).unwrap();
-        let bin_path = PathBuf::from(output.trim()).join(&cfg.target);
+        let args = vec!["test", "--filter", &cfg.target];
+        let mut process = Process::new(&self.toolchain.path);
 
-        log::info!("Running {:?} via {bin_path:?}", self.name());
+        process.args(&args);
+        process.current_dir(self.root());
 
-        Ok((
-            Box::new(BinRunner::from_path(&bin_path)),
-            build_stream,
-            args,
-        ))
+        let stream = Box::pin(process.spawn_and_stream()?);
+
+        Ok((process_build_events(stream), vec![]))
     }
 }
 
 #[async_trait::async_trait]
 impl ProjectCompile for SwiftProject {
+    /// Generate compile database from `swift build -v` output when the package is served by
+    /// xbase build server, i.e. `buildServer.json` exists in package root.
+    ///
+    /// The build cache is kept between runs, so only rebuilt modules are merged into the
+    /// existing compile database.
     async fn update_compile_database(&self) -> Result<()> {
-        // No Compile database needed for swif projects
+        let root = self.root();
+        if !root.join("buildServer.json").exists() {
+            return Ok(());
+        }
+
+        let cache_root = crate::util::fs::get_build_cache_dir(root)?;
+        let args = ["build", "-v", "--build-path", &cache_root];
+
+        log::info!("swift {}", args.join(" "));
+
+        let output = Command::new(&self.toolchain.path)
+            .args(args)
+            .current_dir(root)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if !output.status.success() {
+            log::error!("[{}] swift build failed", self.name());
+            return Err(Error::Build(stderr.to_string()));
+        }
+
+        let updates = stdout
+            .lines()
+            .chain(stderr.lines())
+            .filter_map(|line| CompileCommand::parse(line, root))
+            .collect::<Vec<_>>();
+
+        log::debug!(
+            "[{}] compiled successfully ({} commands)",
+            self.name(),
+            updates.len()
+        );
+
+        let existing = tokio::fs::read(root.join(".compile"))
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+        let compile_commands = CompileCommand::merge(existing, updates);
+
+        let json = serde_json::to_vec_pretty(&compile_commands)?;
+        tokio::fs::write(root.join(".compile"), &json).await?;
+
         Ok(())
     }
+
+    /// Swift build resolves affected modules itself
+    async fn update_compile_database_incremental(&self, _events: &[&Event]) -> Result<()> {
+        self.update_compile_database().await
+    }
 }
 #[async_trait::async_trait]
 impl ProjectGenerate for SwiftProject {
@@ -127,18 +250,22 @@ impl ProjectGenerate for SwiftProject {
         let is_content_update = event.is_content_update_event();
         let is_config_file_update = is_content_update && is_config_file;
 
-        is_config_file_update
-            || event.is_create_event()
-            || event.is_remove_event()
-            || event.is_rename_event()
+        is_config_file_update || self.is_source_tree_update(event)
+    }
+
+    fn source_roots(&self) -> &[PathBuf] {
+        &self.sources
     }
 
     /// Generate xcodeproj
     async fn generate(&mut self) -> Result<()> {
         log::info!("Building and compiling swift project {}", self.name());
 
-        let mut process: Process = vec!["/usr/bin/swift", "build"].into();
+        self.toolchain = Toolchain::resolve(self.root()).await;
+
+        let mut process = Process::new(&self.toolchain.path);
 
+        process.args(&["build"]);
         process.current_dir(self.root());
 
         let (success, logs) = consume_and_log(process.spawn_and_stream()?.boxed()).await;
@@ -150,6 +277,7 @@ impl ProjectGenerate for SwiftProject {
         self.update_project_info().await?;
 
         log::info!("(name: {:?}, targets: {:?})", self.name(), self.targets());
+        log::info!("(name: {:?}, tests: {:?})", self.name(), self.tests);
 
         Ok(())
     }
@@ -164,6 +292,7 @@ impl Project for SwiftProject {
 
         let mut project = Self {
             root: root.clone(),
+            toolchain: Toolchain::resolve(root).await,
             watchignore,
             clients: vec![pid.clone()],
             ..Self::default()
@@ -187,12 +316,32 @@ impl Project for SwiftProject {
 }
 
 impl SwiftProject {
+    /// Run `swift build` with given arguments
+    fn swift_build(&self, args: &[&str]) -> Result<(BuildStream, Vec<String>)> {
+        let mut process = Process::new(&self.toolchain.path);
+
+        process.args(&["build"]);
+        process.args(args);
+        process.current_dir(self.root());
+
+        let stream = Box::pin(process.spawn_and_stream()?);
+
+        Ok((process_build_events(stream), vec![]))
+    }
+
+    /// Get binary name of a given executable target
+    fn executable_name<'a>(&'a self, target: &'a str) -> &'a str {
+        self.executables
+            .get(target)
+            .map(String::as_str)
+            .unwrap_or(target)
+    }
+
     /// Read Package.swift and update internal state
     async fn update_project_info(&mut self) -> Result<()> {
-        use anyhow::anyhow;
         use serde_json::{Map, Value};
 
-        let output = Command::new("/usr/bin/swift")
+        let output = Command::new(&self.toolchain.path)
             .args(["package", "dump-package"])
             .current_dir(self.root())
             .stdin(std::process::Stdio::null())
@@ -214,33 +363,34 @@ impl SwiftProject {
             return Err(Error::DefinitionParsing(error));
         };
 
-        // TODO(swift-package): only provide run service for executables
-        self.name = map
-            .get("name")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .ok_or_else(|| anyhow!("expected package name field is missing!"))?;
-
-        self.targets = map
-            .get("targets")
-            .and_then(|v| v.as_array())
-            .ok_or_else(|| anyhow!("expected package target field is missing!"))?
-            .into_iter()
-            .flat_map(|v| v.as_object())
-            .flat_map(|target_info| {
-                let name = target_info.get("name")?.as_str()?.to_string();
-                if !target_info
-                    .get("type")
-                    .and_then(|s| s.as_str())
-                    .map(|s| s == "test")
-                    .unwrap_or_default()
-                {
-                    Some((name, PBXTargetPlatform::MacOS))
-                } else {
-                    None
-                }
-            })
+        let info = PackageInfo::from_dump(&map)?;
+
+        self.name = info.name;
+
+        self.targets = info
+            .targets
+            .iter()
+            .filter(|(_, kind)| kind.is_buildable())
+            .map(|(name, _)| (name.clone(), PBXTargetPlatform::MacOS))
+            .collect();
+
+        self.tests = info
+            .targets
+            .iter()
+            .filter(|(_, kind)| **kind == SwiftTargetKind::Test)
+            .map(|(name, _)| name.clone())
+            .collect();
+        self.tests.sort();
+
+        self.schemes = info.schemes();
+        self.sources = info
+            .sources
+            .values()
+            .map(|path| self.root.join(path))
             .collect();
+        self.sources.sort();
+        self.kinds = info.targets;
+        self.executables = info.executables;
 
         Ok(())
     }
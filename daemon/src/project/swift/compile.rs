@@ -0,0 +1,151 @@
+//! Compile database generation from `swift build -v` output.
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Compile command in the format read by xbase sourcekit helper
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompileCommand {
+    #[serde(
+        rename = "module_name",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    pub directory: String,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<PathBuf>>,
+}
+
+impl CompileCommand {
+    /// Parse a `swift build -v` output line invoking swiftc or clang
+    pub fn parse(line: &str, directory: &Path) -> Option<Self> {
+        let args = shell_words::split(line.trim()).ok()?;
+        Self::from_args(expand_response_files(args), directory)
+    }
+
+    /// Create from swiftc or clang arguments, starting with the compiler path
+    pub fn from_args(args: Vec<String>, directory: &Path) -> Option<Self> {
+        let program = Path::new(args.first()?).file_name()?.to_str()?;
+        let directory = directory.display().to_string();
+        let command = shell_words::join(&args);
+
+        if program == "swiftc" {
+            let i = args.iter().position(|arg| arg == "-module-name")?;
+            let name = args.get(i + 1)?.clone();
+            let files = args
+                .iter()
+                .filter(|arg| !arg.starts_with('-') && arg.ends_with(".swift"))
+                .map(PathBuf::from)
+                .collect::<Vec<_>>();
+
+            if files.is_empty() {
+                return None;
+            }
+
+            Some(Self {
+                name,
+                file: None,
+                directory,
+                command,
+                files: Some(files),
+            })
+        } else if program.starts_with("clang") {
+            let i = args.iter().position(|arg| arg == "-c")?;
+            let file = args.get(i + 1).map(PathBuf::from)?;
+
+            Some(Self {
+                name: Default::default(),
+                file: Some(file),
+                directory,
+                command,
+                files: None,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Merge compile commands of an incremental build into existing ones. Modules and files that
+    /// were not rebuilt keep their previous commands, unless their files no longer exist.
+    pub fn merge(existing: Vec<Self>, updates: Vec<Self>) -> Vec<Self> {
+        let is_updated = |command: &Self| {
+            updates.iter().any(|update| match command.file {
+                Some(ref file) => update.file.as_ref() == Some(file),
+                None => update.file.is_none() && update.name == command.name,
+            })
+        };
+        let exists = |command: &Self| match command.file {
+            Some(ref file) => file.exists(),
+            None => command.files.iter().flatten().all(|file| file.exists()),
+        };
+
+        let kept = existing
+            .into_iter()
+            .filter(|command| !is_updated(command) && exists(command))
+            .collect::<Vec<_>>();
+
+        kept.into_iter().chain(updates).collect()
+    }
+}
+
+/// Replace `@path` arguments with the arguments listed in the response file
+fn expand_response_files(args: Vec<String>) -> Vec<String> {
+    args.into_iter()
+        .flat_map(|arg| match arg.strip_prefix('@') {
+            Some(path) => match std::fs::read_to_string(path) {
+                Ok(content) => content
+                    .lines()
+                    .flat_map(|line| shell_words::split(line).unwrap_or_default())
+                    .collect(),
+                Err(_) => vec![arg],
+            },
+            None => vec![arg],
+        })
+        .collect()
+}
+
+#[test]
+fn test_parse_compile_command() {
+    let root = Path::new("/p");
+    let line = r#"/usr/bin/swiftc -module-name Demo -emit-module-path "/p/.build/Demo Module.swiftmodule" /p/Sources/Demo/a.swift /p/Sources/Demo/b.swift -I /p/.build"#;
+    let command = CompileCommand::parse(line, root).unwrap();
+    assert_eq!(command.name, "Demo");
+    assert_eq!(
+        command.files,
+        Some(vec![
+            PathBuf::from("/p/Sources/Demo/a.swift"),
+            PathBuf::from("/p/Sources/Demo/b.swift")
+        ])
+    );
+    assert!(command
+        .command
+        .contains("'/p/.build/Demo Module.swiftmodule'"));
+
+    let line = "/usr/bin/clang -fmodule-name=CDemo -I /p/Sources/CDemo/include -c /p/Sources/CDemo/c.c -o /p/.build/c.o";
+    let command = CompileCommand::parse(line, root).unwrap();
+    assert_eq!(command.file, Some(PathBuf::from("/p/Sources/CDemo/c.c")));
+
+    assert!(CompileCommand::parse("Compiling Demo a.swift", root).is_none());
+}
+
+#[test]
+fn test_merge_compile_commands() {
+    let root = Path::new("/");
+    let existing = vec![
+        CompileCommand::parse("/usr/bin/swiftc -module-name A -c /a.swift", root).unwrap(),
+        CompileCommand::parse("/usr/bin/swiftc -module-name B -c /b.swift", root).unwrap(),
+    ];
+    let updates =
+        vec![
+            CompileCommand::parse("/usr/bin/swiftc -module-name A -c /a.swift -Onone", root)
+                .unwrap(),
+        ];
+
+    // NOTE: Commands of files that no longer exist are dropped
+    let merged = CompileCommand::merge(existing, updates);
+    assert_eq!(merged.len(), 1);
+    assert!(merged[0].command.ends_with("-Onone"));
+}
@@ -0,0 +1,221 @@
+//! Swift package targets and products read from `swift package dump-package`.
+use crate::project::{Scheme, SchemeLaunch};
+use crate::{Error, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Swift package target kind
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SwiftTargetKind {
+    Executable,
+    Library,
+    Plugin,
+    Macro,
+    Test,
+    System,
+    Binary,
+}
+
+impl SwiftTargetKind {
+    fn from_target_type(value: &str) -> Option<Self> {
+        match value {
+            "executable" => Some(Self::Executable),
+            "regular" => Some(Self::Library),
+            "plugin" => Some(Self::Plugin),
+            "macro" => Some(Self::Macro),
+            "test" => Some(Self::Test),
+            "system" => Some(Self::System),
+            "binary" => Some(Self::Binary),
+            _ => None,
+        }
+    }
+
+    /// Whether targets of this kind can be built on their own
+    pub fn is_buildable(&self) -> bool {
+        matches!(self, Self::Executable | Self::Library | Self::Macro)
+    }
+}
+
+/// Swift package information
+#[derive(Debug, Default)]
+pub struct PackageInfo {
+    pub name: String,
+    /// Targets and their kinds
+    pub targets: HashMap<String, SwiftTargetKind>,
+    /// Executable products names by target name
+    pub executables: HashMap<String, String>,
+    /// Targets sources directories, relative to the package root
+    pub sources: HashMap<String, PathBuf>,
+}
+
+impl PackageInfo {
+    /// Parse `swift package dump-package` output
+    pub fn from_dump(map: &Map<String, Value>) -> Result<Self> {
+        let missing = |field: &str| {
+            Error::DefinitionParsing(format!("expected package {field} field is missing!"))
+        };
+
+        let name = map
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| missing("name"))?
+            .to_string();
+
+        let mut targets = map
+            .get("targets")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| missing("target"))?
+            .iter()
+            .flat_map(|v| v.as_object())
+            .flat_map(|target| {
+                let name = target.get("name")?.as_str()?.to_string();
+                let kind = target
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .and_then(SwiftTargetKind::from_target_type)
+                    .unwrap_or(SwiftTargetKind::Library);
+                Some((name, kind))
+            })
+            .collect::<HashMap<_, _>>();
+
+        // NOTE: Targets without custom path are located by convention, see SwiftPM
+        // `PredefinedSourceDirectory`
+        let sources = map
+            .get("targets")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .flat_map(|v| v.as_object())
+            .flat_map(|target| {
+                let name = target.get("name")?.as_str()?.to_string();
+                let path = match target.get("path").and_then(|v| v.as_str()) {
+                    Some(path) => PathBuf::from(path),
+                    None => match targets.get(&name)? {
+                        SwiftTargetKind::Test => PathBuf::from("Tests").join(&name),
+                        SwiftTargetKind::Plugin => PathBuf::from("Plugins").join(&name),
+                        _ => PathBuf::from("Sources").join(&name),
+                    },
+                };
+                Some((name, path))
+            })
+            .collect::<HashMap<_, _>>();
+
+        // NOTE: Executable products list their main target first. Before swift 5.4, executable
+        // targets were regular targets with main.swift.
+        let executables = map
+            .get("products")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .flat_map(|v| v.as_object())
+            .filter(|product| {
+                product
+                    .get("type")
+                    .and_then(|v| v.as_object())
+                    .map(|kind| kind.contains_key("executable"))
+                    .unwrap_or_default()
+            })
+            .flat_map(|product| {
+                let name = product.get("name")?.as_str()?.to_string();
+                let target = product.get("targets")?.as_array()?.first()?.as_str()?;
+                Some((target.to_string(), name))
+            })
+            .collect::<HashMap<_, _>>();
+
+        for target in executables.keys() {
+            if let Some(kind) = targets.get_mut(target) {
+                *kind = SwiftTargetKind::Executable;
+            }
+        }
+
+        Ok(Self {
+            name,
+            targets,
+            executables,
+            sources,
+        })
+    }
+
+    /// Schemes Xcode generates for swift packages: `<name>-Package` building every target and
+    /// one per executable product
+    pub fn schemes(&self) -> HashMap<String, Scheme> {
+        let targets_of = |kind: fn(&SwiftTargetKind) -> bool| {
+            let mut targets = self
+                .targets
+                .iter()
+                .filter(|(_, k)| kind(k))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+            targets.sort();
+            targets
+        };
+
+        let package = Scheme {
+            name: format!("{}-Package", self.name),
+            build_targets: targets_of(SwiftTargetKind::is_buildable),
+            test_targets: targets_of(|kind| *kind == SwiftTargetKind::Test),
+            ..Scheme::default()
+        };
+
+        self.executables
+            .iter()
+            .map(|(target, product)| Scheme {
+                name: product.clone(),
+                build_targets: vec![target.clone()],
+                launch: SchemeLaunch {
+                    target: Some(target.clone()),
+                    ..SchemeLaunch::default()
+                },
+                ..Scheme::default()
+            })
+            .chain(std::iter::once(package))
+            .map(|scheme| (scheme.name.clone(), scheme))
+            .collect()
+    }
+}
+
+#[test]
+fn test_package_info() {
+    let dump = serde_json::json!({
+        "name": "Demo",
+        "targets": [
+            { "name": "Demo", "type": "executable" },
+            { "name": "Legacy", "type": "regular" },
+            { "name": "DemoKit", "type": "regular", "path": "Modules/DemoKit" },
+            { "name": "DemoMacros", "type": "macro" },
+            { "name": "Lint", "type": "plugin" },
+            { "name": "DemoTests", "type": "test" }
+        ],
+        "products": [
+            { "name": "demo", "type": { "executable": null }, "targets": ["Demo"] },
+            { "name": "legacy", "type": { "executable": null }, "targets": ["Legacy"] },
+            { "name": "DemoKit", "type": { "library": ["automatic"] }, "targets": ["DemoKit"] }
+        ]
+    });
+    let info = PackageInfo::from_dump(dump.as_object().unwrap()).unwrap();
+
+    assert_eq!(info.targets["Demo"], SwiftTargetKind::Executable);
+    assert_eq!(info.targets["Legacy"], SwiftTargetKind::Executable);
+    assert_eq!(info.targets["DemoKit"], SwiftTargetKind::Library);
+    assert_eq!(info.targets["DemoMacros"], SwiftTargetKind::Macro);
+    assert_eq!(info.targets["Lint"], SwiftTargetKind::Plugin);
+    assert_eq!(info.targets["DemoTests"], SwiftTargetKind::Test);
+    assert_eq!(info.executables["Demo"], "demo");
+    assert!(!info.executables.contains_key("DemoKit"));
+    assert_eq!(info.sources["Demo"], PathBuf::from("Sources/Demo"));
+    assert_eq!(info.sources["DemoKit"], PathBuf::from("Modules/DemoKit"));
+    assert_eq!(info.sources["DemoTests"], PathBuf::from("Tests/DemoTests"));
+
+    let schemes = info.schemes();
+    assert_eq!(schemes["Demo-Package"].test_targets, ["DemoTests"]);
+    assert_eq!(
+        schemes["Demo-Package"].build_targets,
+        ["Demo", "DemoKit", "DemoMacros", "Legacy"]
+    );
+    assert_eq!(schemes["demo"].launch.target.as_deref(), Some("Demo"));
+    assert_eq!(schemes.len(), 3);
+}
@@ -0,0 +1,153 @@
+//! Swift toolchain resolution.
+use crate::util::fs::which;
+use crate::{Error, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+lazy_static::lazy_static! {
+    /// Swift binaries requested by clients on register, by project root
+    static ref OVERRIDES: Mutex<HashMap<PathBuf, PathBuf>> = Default::default();
+}
+
+/// Swift binary used when no toolchain could be resolved
+static DEFAULT_SWIFT_PATH: &str = "/usr/bin/swift";
+
+/// Swift toolchain used to build, run and test swift packages
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Toolchain {
+    /// Path to swift binary
+    pub path: PathBuf,
+    /// Where the toolchain was resolved from
+    pub source: String,
+}
+
+impl Default for Toolchain {
+    fn default() -> Self {
+        Self {
+            path: DEFAULT_SWIFT_PATH.into(),
+            source: "default".into(),
+        }
+    }
+}
+
+impl Toolchain {
+    /// Set swift binary requested on register for a given project root
+    pub fn set_override(root: &Path, path: &Path) -> Result<()> {
+        // NOTE: Toolchain managers, e.g. swiftly, install `swift` shims outside toolchains
+        let is_swift = path.file_name().map_or(false, |name| name == "swift");
+        if !is_swift || !path.is_file() {
+            let msg = format!("{path:?} is not a swift binary");
+            return Err(Error::Setup(msg));
+        }
+
+        let mut overrides = OVERRIDES.lock().unwrap();
+        overrides.insert(root.to_path_buf(), path.to_path_buf());
+        Ok(())
+    }
+
+    /// Resolve swift toolchain for a given project root, trying in order project `.swift-version`,
+    /// override requested on register, `TOOLCHAINS` environment variable then `swift` in PATH.
+    pub async fn resolve(root: &Path) -> Self {
+        let toolchain = match Self::from_swift_version(root).await {
+            Some(toolchain) => Some(toolchain),
+            None => match Self::from_override(root) {
+                Some(toolchain) => Some(toolchain),
+                None => Self::from_toolchains().await,
+            },
+        };
+        let toolchain = toolchain.or_else(Self::from_path).unwrap_or_default();
+
+        log::info!("Using swift at {:?} ({})", toolchain.path, toolchain.source);
+        toolchain
+    }
+
+    async fn from_swift_version(root: &Path) -> Option<Self> {
+        let content = tokio::fs::read_to_string(root.join(".swift-version"))
+            .await
+            .ok()?;
+        let version = content.trim();
+        if version.is_empty() {
+            return None;
+        }
+
+        let path = version_candidates(version)
+            .into_iter()
+            .find(|path| path.is_file());
+
+        if path.is_none() {
+            log::warn!("No toolchain found for .swift-version {version}");
+        }
+
+        Some(Self {
+            path: path?,
+            source: format!(".swift-version ({version})"),
+        })
+    }
+
+    fn from_override(root: &Path) -> Option<Self> {
+        let path = OVERRIDES.lock().unwrap().get(root)?.clone();
+        Some(Self {
+            path,
+            source: "swift_toolchain".into(),
+        })
+    }
+
+    /// Resolve swift from Xcode toolchain identifier set in `TOOLCHAINS`
+    async fn from_toolchains() -> Option<Self> {
+        let toolchain = std::env::var("TOOLCHAINS").ok()?;
+        let output = tokio::process::Command::new("xcrun")
+            .args(["--toolchain", &toolchain, "--find", "swift"])
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await
+            .ok()?;
+
+        if !output.status.success() {
+            log::warn!("No swift found for TOOLCHAINS={toolchain}");
+            return None;
+        }
+
+        Some(Self {
+            path: String::from_utf8(output.stdout).ok()?.trim().into(),
+            source: format!("TOOLCHAINS ({toolchain})"),
+        })
+    }
+
+    fn from_path() -> Option<Self> {
+        Some(Self {
+            path: which("swift").ok()?.into(),
+            source: "PATH".into(),
+        })
+    }
+}
+
+/// Locations where swiftenv, swiftly and manual installs put a given swift version
+fn version_candidates(version: &str) -> Vec<PathBuf> {
+    let release = if version.starts_with("swift-") {
+        version.to_string()
+    } else {
+        format!("swift-{version}-RELEASE")
+    };
+    let mut roots = vec![
+        PathBuf::from("/opt/swift").join(version),
+        PathBuf::from(format!("/opt/swift-{version}")),
+        PathBuf::from("/opt").join(&release),
+        PathBuf::from("/Library/Developer/Toolchains").join(format!("{release}.xctoolchain")),
+    ];
+
+    if let Some(home) = dirs::home_dir() {
+        roots.extend([
+            home.join(".swiftenv/versions").join(version),
+            home.join(".local/share/swiftly/toolchains").join(version),
+            home.join("Library/Developer/Toolchains")
+                .join(format!("{release}.xctoolchain")),
+        ]);
+    }
+
+    roots
+        .into_iter()
+        .map(|root| root.join("usr/bin/swift"))
+        .collect()
+}
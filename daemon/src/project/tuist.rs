@@ -4,7 +4,8 @@ use crate::watch::Event;
 use crate::{Error, Result};
 use process_stream::Process;
 use serde::Serialize;
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use xbase_proto::Client;
 use xcodeproj::{pbxproj::PBXTargetPlatform, XCodeProject};
 
@@ -13,18 +14,19 @@ use xcodeproj::{pbxproj::PBXTargetPlatform, XCodeProject};
 pub struct TuistProject {
     root: PathBuf,
     targets: HashMap<String, PBXTargetPlatform>,
+    schemes: HashMap<String, Scheme>,
     clients: Vec<i32>,
     watchignore: Vec<String>,
     #[serde(skip)]
-    xcodeproj: XCodeProject,
-    #[serde(skip)]
-    xcodeproj_path: PathBuf,
+    xcodeprojs: XCodeProjects,
     #[serde(skip)]
     manifest: XCodeProject,
     #[serde(skip)]
     manifest_path: PathBuf,
     #[serde(skip)]
     manifest_files: Vec<String>,
+    #[serde(skip)]
+    source_roots: Vec<PathBuf>,
 }
 
 impl ProjectData for TuistProject {
@@ -33,13 +35,29 @@ impl ProjectData for TuistProject {
     }
 
     fn name(&self) -> &str {
-        &self.xcodeproj.name()
+        self.xcodeprojs.name()
     }
 
     fn targets(&self) -> &HashMap<String, PBXTargetPlatform> {
         &self.targets
     }
 
+    fn workspace(&self) -> Option<&PathBuf> {
+        self.xcodeprojs.workspace.as_ref()
+    }
+
+    fn target_xcodeproj(&self, target: &str) -> Option<&PathBuf> {
+        self.xcodeprojs.owners.get(target)
+    }
+
+    fn shadowed_targets(&self) -> HashMap<String, Vec<PathBuf>> {
+        self.xcodeprojs.shadowed.clone()
+    }
+
+    fn schemes(&self) -> &HashMap<String, Scheme> {
+        &self.schemes
+    }
+
     fn clients(&self) -> &Vec<i32> {
         &self.clients
     }
@@ -59,13 +77,15 @@ impl ProjectBuild for TuistProject {}
 #[async_trait::async_trait]
 impl ProjectRun for TuistProject {}
 
+#[async_trait::async_trait]
+impl ProjectTest for TuistProject {}
+
 #[async_trait::async_trait]
 impl ProjectCompile for TuistProject {
     async fn update_compile_database(&self) -> Result<()> {
         use xclog::XCCompilationDatabase as CC;
         use xclog::XCCompileCommand as C;
 
-        let name = self.name();
         let root = self.root();
         let cache_root = self.build_cache_root()?;
         let arguments = self.compile_arguments();
@@ -87,21 +107,12 @@ impl ProjectCompile for TuistProject {
             compile_commands.extend(CC::generate(&root, &arguments).await?.to_vec());
         }
 
-        // Compile Project
+        // Compile Projects
         {
             let mut arguments = arguments.clone();
+            arguments.push(format!("SYMROOT={cache_root}"));
 
-            arguments.extend_from_slice(&[
-                format!("SYMROOT={cache_root}"),
-                "-workspace".into(),
-                format!("{name}.xcworkspace"),
-                "-scheme".into(),
-                format!("{name}"),
-            ]);
-
-            log::debug!("\n\nxcodebuild {}\n", arguments.join(" "));
-
-            compile_commands.extend(CC::generate(&root, &arguments).await?.to_vec());
+            compile_commands.extend(self.generate_compile_commands(arguments).await?);
         }
 
         log::debug!("[{}] compiled successfully", self.name());
@@ -120,10 +131,15 @@ impl ProjectGenerate for TuistProject {
         let is_content_update = event.is_content_update_event();
         let is_config_file_update = is_content_update && is_config_file;
 
-        is_config_file_update
-            || event.is_create_event()
-            || event.is_remove_event()
-            || event.is_rename_event()
+        is_config_file_update || self.is_source_tree_update(event)
+    }
+
+    fn source_roots(&self) -> &[PathBuf] {
+        &self.source_roots
+    }
+
+    async fn update_source_roots(&mut self) {
+        self.source_roots = xcodeproj_source_roots(&self.root, &self.xcodeprojs).await;
     }
 
     /// Generate xcodeproj
@@ -133,46 +149,37 @@ impl ProjectGenerate for TuistProject {
         self.tuist(&["edit", "--permanent"]).await?;
         self.tuist(&["generate", "--no-open"]).await?;
 
-        let (xcodeproj_path, manifest_path) = self.xcodeproj_paths()?;
-        let (xcodeproj_path, manifest_path) = (xcodeproj_path.unwrap(), manifest_path.unwrap());
+        let (xcodeproj_paths, manifest_path) = self.xcodeproj_paths()?;
+        let manifest_path = manifest_path.ok_or(Error::DefinitionLocating)?;
 
         self.manifest = XCodeProject::new(&manifest_path)?;
         self.manifest_path = manifest_path;
-        self.xcodeproj = XCodeProject::new(&xcodeproj_path)?;
-        self.xcodeproj_path = xcodeproj_path;
-        self.targets = self.xcodeproj.targets_platform();
+        self.load_xcodeprojs(xcodeproj_paths)?;
+        self.update_source_roots().await;
 
         Ok(())
     }
 }
 
 impl TuistProject {
-    pub fn xcodeproj_paths(&self) -> Result<(Option<PathBuf>, Option<PathBuf>)> {
-        let paths = self.get_xcodeproj_paths()?;
-        if paths.is_empty() {
-            return Ok((None, None));
-        }
+    /// Load generated projects, through the generated workspace when there is one
+    fn load_xcodeprojs(&mut self, xcodeproj_paths: Vec<PathBuf>) -> Result<()> {
+        self.xcodeprojs =
+            XCodeProjects::load_filtered(&self.root, xcodeproj_paths, |path| !is_manifest(path))?;
+        self.targets = self.xcodeprojs.targets.clone();
+        self.schemes = Scheme::discover(&self.xcodeprojs.containers(), self.targets.keys());
 
-        let (mut xcodeproj, mut manifest) = (None, None);
-        if paths.len() > 2 {
-            log::warn!(
-                "Expected `2` xcodeproj Manifest and Main but found `{}`",
-                paths.len()
-            )
-        }
+        Ok(())
+    }
 
-        paths.into_iter().for_each(|p| {
-            if p.file_name()
-                .and_then(|s| s.to_str())
-                .map(|s| s.starts_with("Manifests"))
-                .unwrap_or_default()
-            {
-                manifest = p.into();
-            } else {
-                xcodeproj = p.into();
-            }
-        });
-        Ok((xcodeproj, manifest))
+    /// Generated xcodeproj paths in root and Manifests xcodeproj path
+    pub fn xcodeproj_paths(&self) -> Result<(Vec<PathBuf>, Option<PathBuf>)> {
+        let (manifests, xcodeprojs): (Vec<_>, Vec<_>) = self
+            .get_xcodeproj_paths()?
+            .into_iter()
+            .partition(|path| is_manifest(path));
+
+        Ok((xcodeprojs, manifests.into_iter().next()))
     }
 
     /// Run tuist command with given args
@@ -211,26 +218,26 @@ impl Project for TuistProject {
             ..Self::default()
         };
 
-        let (xcodeproj_path, manifest_path) = match project.xcodeproj_paths()? {
-            (Some(xcodeproj_path), Some(manifest_path)) => (xcodeproj_path, manifest_path),
-            (Some(_), None) => {
+        let (xcodeproj_paths, manifest_path) = match project.xcodeproj_paths()? {
+            (xcodeproj_paths, Some(manifest_path)) if !xcodeproj_paths.is_empty() => {
+                (xcodeproj_paths, manifest_path)
+            }
+            (xcodeproj_paths, None) if !xcodeproj_paths.is_empty() => {
                 project.tuist(&["edit", "--permanent"]).await?;
 
                 let (a, b) = project.xcodeproj_paths()?;
-                (a.unwrap(), b.unwrap())
+                (a, b.ok_or(Error::DefinitionLocating)?)
             }
-            (None, Some(_)) => {
+            (_, Some(_)) => {
                 project.tuist(&["generate", "--no-open"]).await?;
 
                 let (a, b) = project.xcodeproj_paths()?;
-                (a.unwrap(), b.unwrap())
+                (a, b.ok_or(Error::DefinitionLocating)?)
             }
             _ => {
                 log::info!("no xcodeproj found at {root:?}");
 
                 project.generate().await?;
-
-                project.targets = project.xcodeproj.targets_platform();
                 project.manifest_files = project.manifest.build_file_names();
 
                 log::info!("[{}] targets: {:?}", project.name(), project.targets());
@@ -243,12 +250,19 @@ impl Project for TuistProject {
         project.manifest_path = manifest_path;
         project.manifest_files = project.manifest.build_file_names();
 
-        project.xcodeproj = XCodeProject::new(&xcodeproj_path)?;
-        project.xcodeproj_path = xcodeproj_path;
-        project.targets = project.xcodeproj.targets_platform();
+        project.load_xcodeprojs(xcodeproj_paths)?;
+        project.update_source_roots().await;
 
         log::info!("[{}] targets: {:?}", project.name(), project.targets());
 
         Ok(project)
     }
 }
+
+/// Whether given path is a generated Manifests project or workspace
+fn is_manifest(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.starts_with("Manifests"))
+        .unwrap_or_default()
+}
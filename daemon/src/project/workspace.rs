@@ -0,0 +1,355 @@
+//! Xcode workspace (`.xcworkspace`) parsing and loading of multiple xcodeproj.
+use crate::util::{fs::normalize, pbxproj, xml::tags};
+use crate::{Error, Result};
+use anyhow::Context;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use xcodeproj::{pbxproj::PBXTargetPlatform, XCodeProject};
+
+/// Xcode workspace and the projects it references
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    pub path: PathBuf,
+    /// Referenced xcodeproj paths
+    pub projects: Vec<PathBuf>,
+}
+
+impl Workspace {
+    /// Read workspace `contents.xcworkspacedata` at given `.xcworkspace` path
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path.join("contents.xcworkspacedata"))?;
+        Ok(Self::parse(path, &content))
+    }
+
+    /// Parse `contents.xcworkspacedata` content of the workspace at given path
+    pub fn parse(path: &Path, content: &str) -> Self {
+        let container = path.parent().unwrap_or(path).to_path_buf();
+        let mut groups = vec![container.clone()];
+        let mut projects = vec![];
+
+        for tag in tags(content) {
+            let group = groups.last().unwrap_or(&container);
+            let location = tag
+                .attributes
+                .get("location")
+                .and_then(|location| resolve_location(location, &container, group));
+
+            match tag.name.as_str() {
+                "Group" if tag.is_closing => {
+                    groups.pop();
+                }
+                "Group" if !tag.is_self_closing => {
+                    let location = location.unwrap_or_else(|| group.clone());
+                    groups.push(location);
+                }
+                "FileRef" if !tag.is_closing => {
+                    if let Some(location) = location {
+                        if location.extension().map_or(false, |ext| ext == "xcodeproj") {
+                            projects.push(location);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            path: path.to_path_buf(),
+            projects,
+        }
+    }
+}
+
+/// Resolve workspace `location` attribute, e.g. `group:App/App.xcodeproj`
+fn resolve_location(location: &str, container: &Path, group: &Path) -> Option<PathBuf> {
+    let path = match location.split_once(':') {
+        Some(("group", path)) => group.join(path),
+        Some(("container", path)) => container.join(path),
+        Some(("absolute", path)) => PathBuf::from(path),
+        // NOTE: `self:` references the project containing an embedded workspace
+        Some(("self", _)) => return None,
+        _ => group.join(location),
+    };
+
+    Some(normalize(&path))
+}
+
+/// Xcode projects of a root, either referenced by its workspace or found in it
+#[derive(Debug, Default)]
+pub struct XCodeProjects {
+    /// Workspace to build with
+    pub workspace: Option<PathBuf>,
+    /// Loaded projects, starting with the main one
+    pub projects: Vec<(PathBuf, XCodeProject)>,
+    /// Targets platforms of all projects
+    pub targets: HashMap<String, PBXTargetPlatform>,
+    /// Project path owning each target
+    pub owners: HashMap<String, PathBuf>,
+    /// Other projects defining a target with the same name, by target name
+    pub shadowed: HashMap<String, Vec<PathBuf>>,
+}
+
+impl XCodeProjects {
+    /// Load projects referenced by workspace in root, otherwise given xcodeproj paths
+    pub fn load(root: &Path, xcodeproj_paths: Vec<PathBuf>) -> Result<Self> {
+        Self::load_filtered(root, xcodeproj_paths, |_| true)
+    }
+
+    /// Load projects referenced by workspace in root, otherwise given xcodeproj paths, ignoring
+    /// workspaces and projects not matching given filter
+    pub fn load_filtered(
+        root: &Path,
+        mut xcodeproj_paths: Vec<PathBuf>,
+        filter: impl Fn(&Path) -> bool,
+    ) -> Result<Self> {
+        let mut workspaces = wax::walk("*.xcworkspace", root)
+            .context("Glob")?
+            .flatten()
+            .map(|entry| entry.into_path())
+            .filter(|path| filter(path))
+            .collect::<Vec<PathBuf>>();
+        xcodeproj_paths.retain(|path| filter(path));
+        workspaces.sort();
+
+        if workspaces.len() > 1 {
+            log::warn!("Found more then one xcworkspace, using {:?}", workspaces[0]);
+        }
+
+        let workspace = match workspaces.into_iter().next() {
+            Some(path) => Some(Workspace::from_path(&path)?),
+            None => None,
+        };
+
+        let mut paths = match workspace {
+            Some(ref workspace) => workspace
+                .projects
+                .iter()
+                .filter(|path| path.exists())
+                .cloned()
+                .collect::<Vec<_>>(),
+            None => vec![],
+        };
+
+        if paths.is_empty() {
+            xcodeproj_paths.sort();
+            paths = xcodeproj_paths;
+        }
+
+        if paths.is_empty() {
+            return Err(Error::DefinitionLocating);
+        }
+
+        // NOTE: The project named after the workspace is the main one
+        let main_name = workspace
+            .as_ref()
+            .and_then(|workspace| workspace.path.file_stem().map(ToOwned::to_owned));
+        if let Some(idx) = paths
+            .iter()
+            .position(|path| path.file_stem().map(ToOwned::to_owned) == main_name)
+        {
+            let main = paths.remove(idx);
+            paths.insert(0, main);
+        }
+
+        let mut projects = Self {
+            workspace: workspace.map(|workspace| workspace.path),
+            ..Self::default()
+        };
+
+        for path in paths {
+            let xcodeproj = match XCodeProject::new(&path) {
+                Ok(xcodeproj) => xcodeproj,
+                Err(err) => {
+                    log::error!("Fail to read {path:?}: {err}");
+                    continue;
+                }
+            };
+
+            for (target, platform) in xcodeproj.targets_platform() {
+                if projects.owners.contains_key(&target) {
+                    log::warn!("[{target}] is defined in multiple projects, using the first one");
+                    projects
+                        .shadowed
+                        .entry(target)
+                        .or_default()
+                        .push(path.clone());
+                    continue;
+                }
+                projects.owners.insert(target.clone(), path.clone());
+                projects.targets.insert(target, platform);
+            }
+
+            projects.projects.push((path, xcodeproj));
+        }
+
+        if projects.projects.is_empty() {
+            return Err(Error::DefinitionLocating);
+        }
+
+        Ok(projects)
+    }
+
+    /// Main project name
+    pub fn name(&self) -> &str {
+        self.projects
+            .first()
+            .map(|(_, xcodeproj)| xcodeproj.name())
+            .unwrap_or_default()
+    }
+
+    /// Workspace and project paths to read schemes from
+    pub fn containers(&self) -> Vec<PathBuf> {
+        self.workspace
+            .iter()
+            .cloned()
+            .chain(self.projects.iter().map(|(path, _)| path.clone()))
+            .collect()
+    }
+
+    /// Directories of groups of all projects
+    pub fn group_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![];
+        for (path, _) in self.projects.iter() {
+            match std::fs::read_to_string(path.join("project.pbxproj")) {
+                Ok(content) => dirs.extend(group_dirs(path, &content)),
+                Err(err) => log::error!("Fail to read {path:?} groups: {err}"),
+            }
+        }
+        dirs
+    }
+}
+
+/// Get directories of groups defined in `project.pbxproj` content of given xcodeproj, starting
+/// from project main group. Groups without a path only organize their children.
+pub fn group_dirs(xcodeproj: &Path, content: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    let plist = match pbxproj::parse(content) {
+        Some(plist) => plist,
+        None => {
+            log::error!("Fail to parse {xcodeproj:?}");
+            return dirs;
+        }
+    };
+
+    let objects = plist.get("objects");
+    let object = |id: &str| objects.and_then(|objects| objects.get(id));
+    let project = match plist.get_str("rootObject").and_then(object) {
+        Some(project) => project,
+        None => return dirs,
+    };
+
+    let project_dir = xcodeproj
+        .parent()
+        .unwrap_or(xcodeproj)
+        .join(project.get_str("projectDirPath").unwrap_or_default());
+    let mut groups = project
+        .get_str("mainGroup")
+        .map(|id| (id, project_dir.clone()))
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    while let Some((id, parent)) = groups.pop() {
+        let group = match object(id) {
+            Some(group) => group,
+            None => continue,
+        };
+        let path = group.get_str("path");
+        let dir = match (group.get_str("sourceTree"), path) {
+            (Some("<group>"), Some(path)) => parent.join(path),
+            (Some("<group>"), None) => parent,
+            (Some("SOURCE_ROOT"), Some(path)) => project_dir.join(path),
+            (Some("SOURCE_ROOT"), None) => project_dir.clone(),
+            (Some("<absolute>"), Some(path)) => PathBuf::from(path),
+            _ => continue,
+        };
+
+        match group.get_str("isa") {
+            Some("PBXGroup") => {
+                let children = group
+                    .get("children")
+                    .and_then(|children| children.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|child| child.as_str());
+                groups.extend(children.map(|child| (child, dir.clone())));
+            }
+            // NOTE: Files of synchronized folders (Xcode 16) are not listed in the project
+            Some("PBXFileSystemSynchronizedRootGroup") => {}
+            _ => continue,
+        }
+
+        if path.is_some() {
+            dirs.push(normalize(&dir));
+        }
+    }
+
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+#[test]
+fn test_parse_workspace() {
+    let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Workspace
+   version = "1.0">
+   <FileRef
+      location = "group:App/App.xcodeproj">
+   </FileRef>
+   <Group
+      location = "group:Modules"
+      name = "Modules">
+      <FileRef
+         location = "group:Core/Core.xcodeproj">
+      </FileRef>
+      <FileRef
+         location = "group:../Shared/Shared.xcodeproj">
+      </FileRef>
+   </Group>
+   <FileRef
+      location = "container:Tools/Tools.xcodeproj">
+   </FileRef>
+   <FileRef
+      location = "group:README.md">
+   </FileRef>
+</Workspace>"#;
+
+    let workspace = Workspace::parse(Path::new("/repo/Demo.xcworkspace"), content);
+    assert_eq!(
+        workspace.projects,
+        [
+            PathBuf::from("/repo/App/App.xcodeproj"),
+            PathBuf::from("/repo/Modules/Core/Core.xcodeproj"),
+            PathBuf::from("/repo/Shared/Shared.xcodeproj"),
+            PathBuf::from("/repo/Tools/Tools.xcodeproj"),
+        ]
+    );
+}
+
+#[test]
+fn test_group_dirs() {
+    let content = r#"{
+	objects = {
+		P1 /* Project object */ = {isa = PBXProject; mainGroup = G1; projectDirPath = ""; };
+		G1 = {isa = PBXGroup; children = (G2, G3, G5, F1); sourceTree = "<group>"; };
+		G2 /* App */ = {isa = PBXGroup; children = (G4, F2); path = App; sourceTree = "<group>"; };
+		G3 /* Modules */ = {isa = PBXGroup; children = (G6); name = Modules; sourceTree = "<group>"; };
+		G4 /* Views */ = {isa = PBXGroup; children = (); path = Views; sourceTree = "<group>"; };
+		G5 /* Products */ = {isa = PBXGroup; children = (F3); name = Products; sourceTree = BUILT_PRODUCTS_DIR; };
+		G6 /* Core */ = {isa = PBXFileSystemSynchronizedRootGroup; path = ../Core; sourceTree = "<group>"; };
+		F1 /* README.md */ = {isa = PBXFileReference; path = README.md; sourceTree = "<group>"; };
+		F2 /* App.swift */ = {isa = PBXFileReference; path = App.swift; sourceTree = "<group>"; };
+		F3 /* App.app */ = {isa = PBXFileReference; path = App.app; sourceTree = BUILT_PRODUCTS_DIR; };
+	};
+	rootObject = P1 /* Project object */;
+}"#;
+
+    assert_eq!(
+        group_dirs(Path::new("/repo/Demo/Demo.xcodeproj"), content),
+        [
+            PathBuf::from("/repo/Core"),
+            PathBuf::from("/repo/Demo/App"),
+            PathBuf::from("/repo/Demo/App/Views"),
+        ]
+    );
+}
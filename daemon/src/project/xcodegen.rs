@@ -1,22 +1,28 @@
+mod spec;
+
 use super::*;
 use crate::util::fs::which;
 use crate::watch::Event;
 use crate::{Error, Result};
 use process_stream::Process;
 use serde::Serialize;
+use spec::XCodeGenSpec;
 use std::{collections::HashMap, path::PathBuf};
 use xbase_proto::Client;
-use xcodeproj::{pbxproj::PBXTargetPlatform, XCodeProject};
+use xcodeproj::pbxproj::PBXTargetPlatform;
 
 #[derive(Debug, Serialize, Default)]
 #[serde(default)]
 pub struct XCodeGenProject {
     root: PathBuf,
     targets: HashMap<String, PBXTargetPlatform>,
+    schemes: HashMap<String, Scheme>,
     clients: Vec<i32>,
     watchignore: Vec<String>,
     #[serde(skip)]
-    xcodeproj: xcodeproj::XCodeProject,
+    xcodeprojs: XCodeProjects,
+    #[serde(skip)]
+    spec: XCodeGenSpec,
 }
 
 impl ProjectData for XCodeGenProject {
@@ -25,13 +31,29 @@ impl ProjectData for XCodeGenProject {
     }
 
     fn name(&self) -> &str {
-        &self.xcodeproj.name()
+        self.xcodeprojs.name()
     }
 
     fn targets(&self) -> &HashMap<String, PBXTargetPlatform> {
         &self.targets
     }
 
+    fn workspace(&self) -> Option<&PathBuf> {
+        self.xcodeprojs.workspace.as_ref()
+    }
+
+    fn target_xcodeproj(&self, target: &str) -> Option<&PathBuf> {
+        self.xcodeprojs.owners.get(target)
+    }
+
+    fn shadowed_targets(&self) -> HashMap<String, Vec<PathBuf>> {
+        self.xcodeprojs.shadowed.clone()
+    }
+
+    fn schemes(&self) -> &HashMap<String, Scheme> {
+        &self.schemes
+    }
+
     fn clients(&self) -> &Vec<i32> {
         &self.clients
     }
@@ -51,21 +73,20 @@ impl ProjectBuild for XCodeGenProject {}
 #[async_trait::async_trait]
 impl ProjectRun for XCodeGenProject {}
 
+#[async_trait::async_trait]
+impl ProjectTest for XCodeGenProject {}
+
 #[async_trait::async_trait]
 impl ProjectCompile for XCodeGenProject {
     async fn update_compile_database(&self) -> Result<()> {
-        use xclog::XCCompilationDatabase as CC;
-
         let root = self.root();
         let cache_root = self.build_cache_root()?;
         let mut arguments = self.compile_arguments();
 
         arguments.push(format!("SYMROOT={cache_root}"));
 
-        log::debug!("\n\nxcodebuild {}\n", arguments.join(" "));
-
-        let compile_db = CC::generate(&root, &arguments).await?;
-        let json = serde_json::to_vec_pretty(&compile_db)?;
+        let compile_commands = self.generate_compile_commands(arguments).await?;
+        let json = serde_json::to_vec_pretty(&compile_commands)?;
         log::debug!("[{}] compiled successfully", self.name());
         tokio::fs::write(root.join(".compile"), &json).await?;
 
@@ -75,14 +96,18 @@ impl ProjectCompile for XCodeGenProject {
 #[async_trait::async_trait]
 impl ProjectGenerate for XCodeGenProject {
     fn should_generate(&self, event: &Event) -> bool {
-        let is_config_file = event.file_name() == "project.yml";
-        let is_content_update = event.is_content_update_event();
-        let is_config_file_update = is_content_update && is_config_file;
-
-        is_config_file_update
-            || event.is_create_event()
-            || event.is_remove_event()
-            || event.is_rename_event()
+        let is_config_file =
+            event.file_name() == "project.yml" || self.spec.is_spec_file(event.path());
+        // NOTE: Editors saving atomically replace spec files, emitting create or rename events
+        let is_update =
+            event.is_content_update_event() || event.is_create_event() || event.is_rename_event();
+        let is_config_file_update = is_update && is_config_file;
+
+        is_config_file_update || self.is_source_tree_update(event)
+    }
+
+    fn source_roots(&self) -> &[PathBuf] {
+        &self.spec.sources
     }
 
     /// Generate xcodeproj
@@ -94,17 +119,13 @@ impl ProjectGenerate for XCodeGenProject {
 
         let (success, logs) = consume_and_log(Box::pin(process.spawn_and_stream()?)).await;
 
+        // NOTE: Includes and sources may have changed with the spec being regenerated from
+        self.spec = XCodeGenSpec::load(&self.root).await;
+
         if success {
-            let xcodeproj_paths = self.get_xcodeproj_paths()?;
-
-            if xcodeproj_paths.len() > 1 {
-                log::warn!(
-                    "Found more then on xcodeproj, using {:?}",
-                    xcodeproj_paths[0]
-                );
-            }
-            self.xcodeproj = XCodeProject::new(&xcodeproj_paths[0])?;
-            self.targets = self.xcodeproj.targets_platform();
+            self.xcodeprojs = XCodeProjects::load(&self.root, self.get_xcodeproj_paths()?)?;
+            self.targets = self.xcodeprojs.targets.clone();
+            self.schemes = Scheme::discover(&self.xcodeprojs.containers(), self.targets.keys());
         } else {
             return Err(Error::Generate(logs.join("\n")));
         }
@@ -125,21 +146,17 @@ impl Project for XCodeGenProject {
             root: root.clone(),
             watchignore,
             clients: vec![pid.clone()],
+            spec: XCodeGenSpec::load(root).await,
             ..Self::default()
         };
 
         let xcodeproj_paths = project.get_xcodeproj_paths()?;
 
-        if xcodeproj_paths.len() > 1 {
-            log::warn!(
-                "Found more then on xcodeproj, using {:?}",
-                xcodeproj_paths[0]
-            );
-        }
-
         if !xcodeproj_paths.is_empty() {
-            project.xcodeproj = XCodeProject::new(&xcodeproj_paths[0])?;
-            project.targets = project.xcodeproj.targets_platform();
+            project.xcodeprojs = XCodeProjects::load(root, xcodeproj_paths)?;
+            project.targets = project.xcodeprojs.targets.clone();
+            project.schemes =
+                Scheme::discover(&project.xcodeprojs.containers(), project.targets.keys());
         } else {
             project.generate().await?;
         }
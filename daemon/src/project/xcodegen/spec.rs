@@ -0,0 +1,186 @@
+//! XcodeGen spec (`project.yml`) include graph and target sources roots.
+use crate::util::fs::normalize;
+use serde_yaml::Value;
+use std::path::{Path, PathBuf};
+
+/// Spec files and sources roots changes to regenerate the xcodeproj on
+#[derive(Debug, Default, Clone)]
+pub struct XCodeGenSpec {
+    /// `project.yml` and every spec file it includes
+    pub files: Vec<PathBuf>,
+    /// Targets sources roots
+    pub sources: Vec<PathBuf>,
+}
+
+/// Spec file content relevant to regeneration
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SpecContent {
+    /// Included spec files and whether their paths are relative to themselves
+    includes: Vec<(PathBuf, bool)>,
+    sources: Vec<PathBuf>,
+}
+
+impl XCodeGenSpec {
+    /// Load `project.yml` in root and follow its includes
+    pub async fn load(root: &Path) -> Self {
+        let mut spec = Self::default();
+        let mut pending = vec![(root.join("project.yml"), root.to_path_buf())];
+
+        while let Some((path, base)) = pending.pop() {
+            // NOTE: Guard against include cycles and files included more then once
+            if spec.files.contains(&path) {
+                continue;
+            }
+
+            let content = match tokio::fs::read_to_string(&path).await {
+                Ok(content) => content,
+                Err(err) => {
+                    log::error!("Fail to read {path:?}: {err}");
+                    continue;
+                }
+            };
+
+            let dir = path.parent().unwrap_or(root).to_path_buf();
+            let content = match SpecContent::parse(&content, &dir, &base) {
+                Ok(content) => content,
+                Err(err) => {
+                    log::error!("Fail to parse {path:?}: {err}");
+                    spec.files.push(path);
+                    continue;
+                }
+            };
+
+            for (include, is_relative) in content.includes {
+                // NOTE: Only files under the root are watched, changes to others are missed
+                if !include.starts_with(root) {
+                    log::warn!("{include:?} is outside {root:?}, changes won't regenerate project");
+                }
+                let base = match is_relative {
+                    true => include.parent().unwrap_or(&dir).to_path_buf(),
+                    false => base.clone(),
+                };
+                pending.push((include, base));
+            }
+
+            spec.sources.extend(content.sources);
+            spec.files.push(path);
+        }
+
+        spec.sources.sort();
+        spec.sources.dedup();
+        spec
+    }
+
+    /// Whether the given path is one of the spec files
+    pub fn is_spec_file(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file == path)
+    }
+}
+
+impl SpecContent {
+    /// Parse spec content, with includes resolved from `dir` and sources from `base`
+    fn parse(content: &str, dir: &Path, base: &Path) -> Result<Self, serde_yaml::Error> {
+        let value: Value = serde_yaml::from_str(content)?;
+        let mut spec = Self::default();
+
+        // NOTE: include is either a path, a `{path, relativePaths}` map or a list of both
+        let includes = match value.get("include") {
+            Some(Value::Sequence(includes)) => includes.iter().collect(),
+            Some(include) => vec![include],
+            None => vec![],
+        };
+
+        for include in includes {
+            let (path, is_relative) = match include {
+                Value::String(path) => (path.as_str(), true),
+                Value::Mapping(_) => match include.get("path").and_then(Value::as_str) {
+                    Some(path) => {
+                        let is_relative = include
+                            .get("relativePaths")
+                            .and_then(Value::as_bool)
+                            .unwrap_or(true);
+                        (path, is_relative)
+                    }
+                    None => continue,
+                },
+                _ => continue,
+            };
+            spec.includes
+                .push((normalize(&dir.join(path)), is_relative));
+        }
+
+        let targets = value.get("targets").and_then(Value::as_mapping);
+        for (_, target) in targets.into_iter().flatten() {
+            // NOTE: sources is either a path, a `{path, ..}` map or a list of both
+            let sources = match target.get("sources") {
+                Some(Value::Sequence(sources)) => sources.iter().collect(),
+                Some(source) => vec![source],
+                None => vec![],
+            };
+
+            for source in sources {
+                let path = match source {
+                    Value::String(path) => path.as_str(),
+                    Value::Mapping(_) => match source.get("path").and_then(Value::as_str) {
+                        Some(path) => path,
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                spec.sources.push(normalize(&base.join(path)));
+            }
+        }
+
+        Ok(spec)
+    }
+}
+
+#[test]
+fn test_parse_spec_content() {
+    let content = r#"
+name: App
+include:
+  - targets/app.yml
+  - path: ../shared/base.yml
+    relativePaths: false
+targets:
+  App:
+    type: application
+    platform: iOS
+    sources:
+      - App
+      - path: Resources
+        buildPhase: resources
+  AppTests:
+    type: bundle.unit-test
+    platform: iOS
+    sources: Tests
+"#;
+
+    let spec = SpecContent::parse(content, Path::new("/repo"), Path::new("/repo")).unwrap();
+    assert_eq!(
+        spec.includes,
+        [
+            (PathBuf::from("/repo/targets/app.yml"), true),
+            (PathBuf::from("/shared/base.yml"), false),
+        ]
+    );
+
+    let mut sources = spec.sources;
+    sources.sort();
+    assert_eq!(
+        sources,
+        [
+            PathBuf::from("/repo/App"),
+            PathBuf::from("/repo/Resources"),
+            PathBuf::from("/repo/Tests"),
+        ]
+    );
+
+    let content = "include: base.yml\n";
+    let spec = SpecContent::parse(content, Path::new("/repo/specs"), Path::new("/repo")).unwrap();
+    assert_eq!(
+        spec.includes,
+        [(PathBuf::from("/repo/specs/base.yml"), true)]
+    );
+}
@@ -1,55 +1,131 @@
 use crate::compile;
 use crate::constants::DAEMON_STATE;
+use crate::project::Toolchain;
+use crate::state::State;
 use crate::Error;
 use crate::RequestHandler;
 use crate::Result;
 use async_trait::async_trait;
-use xbase_proto::RegisterRequest;
+use tokio::sync::MutexGuard;
+use xbase_proto::{Client, RegisterRequest};
 
 #[async_trait]
 impl RequestHandler for RegisterRequest {
     async fn handle(self) -> Result<()> {
-        let Self { client } = &self;
+        let Self {
+            client,
+            compile_swift_package,
+            swift_toolchain,
+        } = &self;
+        let is_headless = client.is_headless();
         let (title, sep) = crate::util::handler_log_content("Register", &client);
         log::info!("{sep}");
         log::info!("{title}");
         log::info!("{sep}");
 
-        let state = DAEMON_STATE.clone();
-        let ref mut state = state.lock().await;
+        // NOTE: Nested packages and projects are registered as projects of their own
+        let mut roots = crate::project::discover_roots(&client.root).await;
+        if roots.is_empty() {
+            roots.push(client.root.clone());
+        }
 
-        if let Ok(project) = state.projects.get_mut(&client.root) {
-            project.add_client(client.pid);
-        } else {
-            state.projects.add(client).await?;
-            let project = state.projects.get(&client.root).unwrap();
-            let watchignore = project.watchignore().clone();
-            let name = project.name().to_string();
+        // NOTE: State is released before setup, which locks it again
+        let clients = {
+            let state = DAEMON_STATE.clone();
+            let ref mut state = state.lock().await;
+            let mut clients = vec![];
 
-            state.watcher.add(client, watchignore, &name).await?;
-        }
+            state.snapshot.add_registration(&self);
+
+            for root in roots {
+                let client = Client {
+                    root,
+                    ..client.clone()
+                };
 
-        // NOTE: The following blocks register request due to nvim_rs rpc
-        let client = client.clone();
-        tokio::spawn(async move {
-            let client = &client;
+                if let Some(path) = swift_toolchain {
+                    if crate::project::is_swift_package(&client.root) {
+                        if let Err(err) = Toolchain::set_override(&client.root, path) {
+                            log::error!("Ignoring swift toolchain override: {err}");
+                        }
+                    }
+                }
+
+                if let Err(err) = register(state, &client, *compile_swift_package).await {
+                    if client.root != self.client.root {
+                        log::error!("Fail to register {:?}: {err}", client.root);
+                        continue;
+                    }
+                    return Err(err);
+                }
+
+                clients.push(client);
+            }
+
+            clients
+        };
+
+        let setup = async move {
             let state = DAEMON_STATE.clone();
             let ref mut state = state.lock().await;
-            state.clients.add(client).await?;
-
-            if compile::ensure_server_support(state, client, None).await? {
-                let ref name = client.abbrev_root();
-                state
-                    .clients
-                    .echo_msg(&client.root, name, "setup: ✅")
-                    .await;
+
+            for client in clients.iter() {
+                state.clients.add(client).await?;
+
+                for request in state.snapshot.take(&client.root) {
+                    if let Err(err) = request.restore(state, client).await {
+                        log::error!("Fail to restore watcher: {err}");
+                    }
+                }
+
+                if compile::ensure_server_support(state, client, &[]).await? {
+                    let ref name = client.abbrev_root();
+                    state
+                        .clients
+                        .echo_msg(&client.root, name, "setup: ✅")
+                        .await;
+                }
             }
 
             state.sync_client_state().await?;
 
             Ok::<_, Error>(())
-        });
+        };
+
+        if is_headless {
+            setup.await?;
+        } else {
+            // NOTE: The following blocks register request due to nvim_rs rpc
+            tokio::spawn(setup);
+        }
 
         Ok(())
     }
 }
+
+/// Add project at client root, or add client to it when it is already registered
+async fn register(
+    state: &mut MutexGuard<'_, State>,
+    client: &Client,
+    compile_swift_package: bool,
+) -> Result<()> {
+    if compile_swift_package && crate::project::is_swift_package(&client.root) {
+        compile::ensure_server_config(&client.root).await?;
+    }
+
+    if let Ok(project) = state.projects.get_mut(&client.root) {
+        // NOTE: Clients register again after daemon restarts rehydrated them
+        if !project.clients().contains(&client.pid) {
+            project.add_client(client.pid);
+        }
+    } else {
+        state.projects.add(client).await?;
+        let project = state.projects.get(&client.root).unwrap();
+        let watchignore = project.watchignore().clone();
+        let name = project.name().to_string();
+
+        state.watcher.add(client, watchignore, &name).await?;
+    }
+
+    Ok(())
+}
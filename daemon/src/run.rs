@@ -1,26 +1,28 @@
 mod bin;
 mod handler;
+mod lazy;
 mod service;
 mod simulator;
 
 use crate::constants::DAEMON_STATE;
 use crate::device::Device;
-use crate::nvim::Logger;
+use crate::sink::Logger;
 use crate::state::State;
 use crate::Error;
 use crate::{RequestHandler, Result};
 use async_trait::async_trait;
 use process_stream::Process;
 use tokio::sync::MutexGuard;
-use xbase_proto::{BuildSettings, Client, RunRequest};
+use xbase_proto::{BuildSettings, Client, LaunchOptions, RunRequest};
 
+pub use handler::RunServiceHandler;
 pub use service::RunService;
-pub use {bin::*, simulator::*};
+pub use {bin::*, lazy::*, simulator::*};
 
 #[async_trait::async_trait]
 pub trait Runner {
-    /// Run Project
-    async fn run<'a>(&self, logger: &mut Logger<'a>) -> Result<Process>;
+    /// Run Project with given arguments, environment variables and working directory
+    async fn run<'a>(&self, logger: &mut Logger<'a>, launch: &LaunchOptions) -> Result<Process>;
 }
 
 #[async_trait]
@@ -42,6 +44,8 @@ impl RequestHandler for RunRequest {
         if self.ops.is_once() {
             // TODO(run): might want to keep track of ran services
             RunService::new(state, self).await?;
+            // NOTE: Status is read by clients waiting for the runner to exit
+            state.sync_client_state().await?;
             return Ok(());
         }
 
@@ -84,15 +88,18 @@ impl RequestHandler for RunRequest {
 
 async fn get_runner<'a>(
     state: &'a MutexGuard<'_, State>,
+    key: &str,
     client: &Client,
     settings: &BuildSettings,
     device: Option<&Device>,
+    launch: &LaunchOptions,
     is_once: bool,
 ) -> Result<process_stream::Process> {
     let root = &client.root;
     let nvim = state.clients.get(&client.pid)?;
 
     let logger = &mut nvim.logger();
+    logger.set_key(key);
 
     if !is_once {
         logger.open_win().await?;
@@ -100,7 +107,15 @@ async fn get_runner<'a>(
     }
 
     let target = &settings.target;
-    let (runner, stream, args) = state.projects.get(root)?.get_runner(&settings, device)?;
+    let project = state.projects.get(root)?;
+    let (runner, stream, args) = project.get_runner(&settings, device)?;
+
+    // NOTE: Default to scheme launch arguments and environment when none are given
+    let scheme = settings.scheme.as_deref().unwrap_or(target);
+    let launch = match project.scheme(scheme) {
+        Some(scheme) if launch.is_empty() => scheme.launch_options(),
+        _ => launch.clone(),
+    };
 
     logger.set_title(format!("Build:{target}"));
     log::info!("[target: {target}] building .....");
@@ -108,7 +123,7 @@ async fn get_runner<'a>(
     let success = logger.consume_build_logs(stream, true, !is_once).await?;
     if !success {
         let msg = format!("[target: {target}] failed to be built",);
-        logger.nvim.echo_err(&msg).await?;
+        logger.client.echo_err(&msg).await?;
         log::error!("[target: {target}] failed to be built");
         log::error!("[ran: 'xcodebuild {}']", args.join(" "));
         return Err(Error::Build(msg));
@@ -119,7 +134,7 @@ async fn get_runner<'a>(
     logger.set_title(format!("Run:{target}"));
     logger.set_running(true).await?;
 
-    let process = runner.run(logger).await?;
+    let process = runner.run(logger, &launch).await?;
     log::info!("[target: {target}] running .....");
 
     Ok(process)
@@ -2,6 +2,7 @@ use crate::run::Logger;
 use crate::{Error, Result};
 use process_stream::Process;
 use std::path::{Path, PathBuf};
+use xbase_proto::LaunchOptions;
 use xclog::XCBuildSettings;
 
 use super::Runner;
@@ -24,11 +25,19 @@ impl BinRunner {
 
 #[async_trait::async_trait]
 impl Runner for BinRunner {
-    async fn run<'a>(&self, _logger: &mut Logger<'a>) -> Result<Process> {
+    async fn run<'a>(&self, _logger: &mut Logger<'a>, launch: &LaunchOptions) -> Result<Process> {
         if !self.path.exists() {
             return Err(Error::Run(format!("{:?} doesn't exist!", self.path)));
         }
 
-        Ok(Process::new(&self.path))
+        let mut process = Process::new(&self.path);
+
+        process.args(&launch.args);
+        process.envs(&launch.env);
+        if let Some(cwd) = &launch.cwd {
+            process.current_dir(cwd);
+        }
+
+        Ok(process)
     }
 }
@@ -1,6 +1,10 @@
 #![allow(dead_code)]
-use crate::{constants::DAEMON_STATE, Result};
+use crate::{constants::DAEMON_STATE, util::pid, Result};
 use process_stream::{Process, StreamExt};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
 use tokio::task::JoinHandle;
 use xbase_proto::Client;
 
@@ -8,6 +12,8 @@ use xbase_proto::Client;
 pub struct RunServiceHandler {
     process: Process,
     inner: JoinHandle<Result<()>>,
+    pid: Arc<AtomicU32>,
+    started: SystemTime,
 }
 
 impl RunServiceHandler {
@@ -22,6 +28,19 @@ impl RunServiceHandler {
         let mut stream = process.spawn_and_stream()?;
         let kill_send = process.clone_kill_sender().unwrap();
 
+        // NOTE: simctl launch reports the app pid, host binaries are spawned by the daemon
+        let command = process.as_std();
+        let is_simctl_launch = command.get_args().any(|arg| arg == "simctl")
+            && command.get_args().any(|arg| arg == "launch");
+        let program = PathBuf::from(command.get_program());
+        let host_pid = match is_simctl_launch {
+            true => None,
+            false => pid::get_child_by_path(&program),
+        };
+
+        let pid = Arc::new(AtomicU32::new(host_pid.unwrap_or_default()));
+        let process_pid = pid.clone();
+
         let inner = tokio::spawn(async move {
             // TODO: find a better way to close this!
             //
@@ -39,11 +58,17 @@ impl RunServiceHandler {
                     }
                 };
 
+                logger.set_key(&key);
                 logger.set_title(format!("Run:{target}"));
 
                 use process_stream::ProcessItem::*;
                 match output {
                     Output(msg) => {
+                        if is_simctl_launch && process_pid.load(Ordering::Relaxed) == 0 {
+                            if let Some(pid) = parse_launch_pid(&msg) {
+                                process_pid.store(pid, Ordering::Relaxed);
+                            }
+                        }
                         if !msg.contains("ignoring singular matrix") {
                             logger.append(msg).await?;
                         }
@@ -74,7 +99,12 @@ impl RunServiceHandler {
             Ok(())
         });
 
-        Ok(Self { process, inner })
+        Ok(Self {
+            process,
+            inner,
+            pid,
+            started: SystemTime::now(),
+        })
     }
 
     /// Get a reference to the run service handler's process.
@@ -88,4 +118,33 @@ impl RunServiceHandler {
     pub fn inner(&self) -> &JoinHandle<Result<()>> {
         &self.inner
     }
+
+    /// Get running process pid, if reported
+    pub fn pid(&self) -> Option<u32> {
+        match self.pid.load(Ordering::Relaxed) {
+            0 => None,
+            pid => Some(pid),
+        }
+    }
+
+    /// Get time at which the process was started
+    pub fn started(&self) -> SystemTime {
+        self.started
+    }
+}
+
+/// Parse `<bundle id>: <pid>` as printed by `simctl launch`
+fn parse_launch_pid(line: &str) -> Option<u32> {
+    let (id, pid) = line.trim().split_once(": ")?;
+    if id.contains(char::is_whitespace) || !id.contains('.') {
+        return None;
+    }
+    pid.parse().ok()
+}
+
+#[test]
+fn test_parse_launch_pid() {
+    assert_eq!(parse_launch_pid("com.xbase.Demo: 4242"), Some(4242));
+    assert_eq!(parse_launch_pid("Hello world: 42"), None);
+    assert_eq!(parse_launch_pid("com.xbase.Demo: started"), None);
 }
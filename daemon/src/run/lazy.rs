@@ -0,0 +1,33 @@
+use crate::run::Runner;
+use crate::sink::Logger;
+use crate::Result;
+use futures::future::BoxFuture;
+use process_stream::Process;
+use xbase_proto::LaunchOptions;
+
+type BoxRunner = Box<dyn Runner + Send + Sync>;
+
+/// Runner resolving the artifact to run only when ran, i.e. after the build it depends on
+/// succeeded.
+pub struct LazyRunner {
+    resolve: Box<dyn Fn() -> BoxFuture<'static, Result<BoxRunner>> + Send + Sync>,
+}
+
+impl LazyRunner {
+    pub fn new<F>(resolve: F) -> Self
+    where
+        F: Fn() -> BoxFuture<'static, Result<BoxRunner>> + Send + Sync + 'static,
+    {
+        Self {
+            resolve: Box::new(resolve),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Runner for LazyRunner {
+    async fn run<'a>(&self, logger: &mut Logger<'a>, launch: &LaunchOptions) -> Result<Process> {
+        let runner = (self.resolve)().await?;
+        runner.run(logger, launch).await
+    }
+}
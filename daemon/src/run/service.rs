@@ -3,6 +3,7 @@ use crate::run::get_runner;
 use crate::{
     device::Device,
     state::State,
+    store::{RunnerEntry, WatchRequest},
     watch::{Event, Watchable},
     Result,
 };
@@ -16,9 +17,11 @@ use xbase_proto::{BuildSettings, Client, RunRequest};
 pub struct RunService {
     pub key: String,
     pub client: Client,
-    pub handler: Arc<Mutex<RunServiceHandler>>,
+    /// Process handler, None until restored services are triggered
+    pub handler: Arc<Mutex<Option<RunServiceHandler>>>,
     pub settings: BuildSettings,
     pub device: Option<Device>,
+    pub request: RunRequest,
 }
 
 impl std::fmt::Display for RunService {
@@ -30,6 +33,7 @@ impl std::fmt::Display for RunService {
 impl RunService {
     pub async fn new(state: &mut MutexGuard<'_, State>, req: RunRequest) -> Result<Self> {
         let key = req.to_string();
+        let request = req.clone();
         let RunRequest {
             client,
             settings,
@@ -40,19 +44,66 @@ impl RunService {
         let device = state.devices.from_lookup(device);
         let is_once = req.ops.is_once();
 
-        let process = get_runner(state, &client, &settings, device.as_ref(), is_once).await?;
+        let process = get_runner(
+            state,
+            &key,
+            &client,
+            &settings,
+            device.as_ref(),
+            &request.launch,
+            is_once,
+        )
+        .await?;
         let handler = RunServiceHandler::new(&key, target, &client, process)?
+            .pipe(Some)
             .pipe(Mutex::new)
             .pipe(Arc::new);
 
+        state.runners.insert(
+            &key,
+            RunnerEntry {
+                root: client.root.clone(),
+                target: target.clone(),
+                device: device.as_ref().map(|d| d.name.clone()),
+                handler: handler.clone(),
+            },
+        );
+
         Ok(Self {
             device,
             handler,
             client,
             settings,
             key,
+            request,
         })
     }
+
+    /// Recreate service of a watch request without launching it until the next change
+    pub fn restore(state: &mut MutexGuard<'_, State>, req: RunRequest) -> Self {
+        let key = req.to_string();
+        let device = state.devices.from_lookup(req.device.clone());
+        let handler = Arc::new(Mutex::new(None));
+
+        state.runners.insert(
+            &key,
+            RunnerEntry {
+                root: req.client.root.clone(),
+                target: req.settings.target.clone(),
+                device: device.as_ref().map(|d| d.name.clone()),
+                handler: handler.clone(),
+            },
+        );
+
+        Self {
+            key,
+            client: req.client.clone(),
+            handler,
+            settings: req.settings.clone(),
+            device,
+            request: req,
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -62,23 +113,26 @@ impl Watchable for RunService {
             key,
             client,
             settings,
+            request,
             ..
         } = self;
 
         let mut handler = self.handler.clone().lock_owned().await;
 
-        handler.process().kill().await;
-        handler.inner().abort();
+        if let Some(handler) = handler.as_ref() {
+            handler.process().kill().await;
+            handler.inner().abort();
+        }
 
         let target = &settings.target;
         let device = self.device.as_ref();
 
-        *handler = RunServiceHandler::new(
+        *handler = Some(RunServiceHandler::new(
             key,
             target,
             client,
-            get_runner(state, client, settings, device, false).await?,
-        )?;
+            get_runner(state, key, client, settings, device, &request.launch, false).await?,
+        )?);
 
         Ok(())
     }
@@ -100,8 +154,14 @@ impl Watchable for RunService {
     /// Drop watchable for watching a given file system
     async fn discard(&self, _state: &MutexGuard<State>) -> Result<()> {
         let handler = self.handler.clone().lock_owned().await;
-        handler.process().kill().await;
-        handler.inner().abort();
+        if let Some(handler) = handler.as_ref() {
+            handler.process().kill().await;
+            handler.inner().abort();
+        }
         Ok(())
     }
+
+    fn request(&self) -> Option<WatchRequest> {
+        Some(WatchRequest::Run(self.request.clone()))
+    }
 }
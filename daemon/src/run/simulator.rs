@@ -1,11 +1,12 @@
 use crate::device::Device;
-use crate::nvim::Logger;
 use crate::run::Runner;
+use crate::sink::Logger;
 use crate::util::{fmt, pid};
 use crate::{Error, Result};
 use process_stream::Process;
 use std::path::PathBuf;
 use tap::Pipe;
+use xbase_proto::LaunchOptions;
 use xclog::XCBuildSettings;
 
 /// Simulator Device runner
@@ -17,10 +18,10 @@ pub struct SimulatorRunner {
 
 #[async_trait::async_trait]
 impl Runner for SimulatorRunner {
-    async fn run<'a>(&self, logger: &mut Logger<'a>) -> Result<Process> {
+    async fn run<'a>(&self, logger: &mut Logger<'a>, launch: &LaunchOptions) -> Result<Process> {
         self.boot(logger).await?;
         self.install(logger).await?;
-        self.launch(logger).await
+        self.launch(logger, launch).await
     }
 }
 
@@ -78,7 +79,13 @@ impl SimulatorRunner {
         Ok(())
     }
 
-    pub async fn launch<'a>(&self, logger: &mut Logger<'a>) -> Result<Process> {
+    /// Launch app, passing launch environment variables to the app through `SIMCTL_CHILD_`
+    /// prefixed variables. Working directory can't be set for simulator apps.
+    pub async fn launch<'a>(
+        &self,
+        logger: &mut Logger<'a>,
+        launch: &LaunchOptions,
+    ) -> Result<Process> {
         logger.append(self.launching_msg()).await?;
         let mut process = Process::new("xcrun");
         let args = &[
@@ -91,6 +98,14 @@ impl SimulatorRunner {
         ];
 
         process.args(args);
+        process.args(&launch.args);
+        for (key, value) in launch.env.iter() {
+            process.env(format!("SIMCTL_CHILD_{key}"), value);
+        }
+
+        if launch.cwd.is_some() {
+            log::warn!("Ignoring working directory for {}", self.app_id);
+        }
 
         logger.append(self.connected_msg()).await?;
         logger.append(fmt::separator()).await?;
@@ -0,0 +1,185 @@
+//! Client sinks, where the daemon reports logs, status and messages to.
+mod file;
+mod logger;
+
+use crate::build::Diagnostic;
+use crate::events;
+use crate::nvim::NvimSink;
+use crate::Result;
+use serde::Serialize;
+use std::path::PathBuf;
+use xbase_proto::{BufferDirection, Client, ClientEventKind};
+
+pub use file::FileSink;
+pub use logger::Logger;
+
+/// Build status reported to clients
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum BuildStatus {
+    Idle,
+    Watching,
+    Running,
+    DeviceRunning,
+    Success,
+    Failure,
+}
+
+/// Backend receiving daemon output for a given client
+#[async_trait::async_trait]
+pub trait ClientSink: Send + Sync {
+    /// Append lines to client log
+    async fn append(&self, lines: &[String]) -> Result<()>;
+
+    /// Clear client log
+    async fn clear(&self) -> Result<()>;
+
+    /// Set current build status
+    async fn set_status(&self, status: BuildStatus) -> Result<()>;
+
+    /// Show a message to the user
+    async fn echo_msg(&self, msg: &str) -> Result<()>;
+
+    /// Show an error to the user
+    async fn echo_err(&self, msg: &str) -> Result<()>;
+
+    /// Update client copy of daemon state
+    async fn sync_state(&self, state: &str) -> Result<()>;
+
+    /// Replace build diagnostics
+    async fn set_diagnostics(&self, _diagnostics: &[Diagnostic]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Make client log visible, e.g. by opening a window
+    async fn open_log(&self, _direction: Option<&BufferDirection>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Registered client and the sinks its output is reported to
+#[derive(Serialize)]
+pub struct ClientHandle {
+    pub pid: i32,
+    pub roots: Vec<PathBuf>,
+    /// Path to file where headless client log is written
+    pub log_path: PathBuf,
+    #[serde(skip)]
+    sinks: Vec<Box<dyn ClientSink>>,
+}
+
+impl ClientHandle {
+    pub async fn new(client: &Client) -> Result<Self> {
+        let Client { root, pid, .. } = client;
+        let log_path = crate::util::fs::get_client_log_path(pid);
+        let sink: Box<dyn ClientSink> = if client.is_headless() {
+            Box::new(FileSink::new(&log_path).await?)
+        } else {
+            Box::new(NvimSink::new(client).await?)
+        };
+        let sinks = vec![sink];
+
+        Ok(Self {
+            pid: *pid,
+            roots: vec![root.to_path_buf()],
+            log_path,
+            sinks,
+        })
+    }
+
+    /// Add a sink to report client output to
+    pub fn add_sink<S: ClientSink + 'static>(&mut self, sink: S) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    pub fn logger<'a>(&'a self) -> Logger<'a> {
+        Logger::new(self)
+    }
+
+    /// Append lines to client log, publishing them as events of the given request key
+    pub async fn append(&self, key: Option<&str>, lines: &[String]) -> Result<()> {
+        let lines_event = ClientEventKind::Log {
+            lines: lines.to_vec(),
+        };
+        events::publish(self.pid, key, lines_event);
+        for sink in self.sinks.iter() {
+            sink.append(lines).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn clear(&self) -> Result<()> {
+        for sink in self.sinks.iter() {
+            sink.clear().await?;
+        }
+        Ok(())
+    }
+
+    /// Set client build status, publishing it as event of the given request key
+    pub async fn set_status(&self, key: Option<&str>, status: BuildStatus) -> Result<()> {
+        let status_event = ClientEventKind::Status {
+            status: status.to_string(),
+        };
+        events::publish(self.pid, key, status_event);
+        for sink in self.sinks.iter() {
+            sink.set_status(status).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn set_watching(&self, is_watching: bool) -> Result<()> {
+        if is_watching {
+            self.set_status(None, BuildStatus::Watching).await
+        } else {
+            self.set_status(None, BuildStatus::Idle).await
+        }
+    }
+
+    pub async fn echo_msg(&self, msg: &str) -> Result<()> {
+        let message = msg.to_string();
+        events::publish(self.pid, None, ClientEventKind::Message { message });
+        for sink in self.sinks.iter() {
+            sink.echo_msg(msg).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn echo_err(&self, msg: &str) -> Result<()> {
+        let message = msg.to_string();
+        events::publish(self.pid, None, ClientEventKind::Error { message });
+        for sink in self.sinks.iter() {
+            sink.echo_err(msg).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn sync_state(&self, state: &str) -> Result<()> {
+        for sink in self.sinks.iter() {
+            sink.sync_state(state).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn set_diagnostics(&self, diagnostics: &[Diagnostic]) -> Result<()> {
+        for sink in self.sinks.iter() {
+            sink.set_diagnostics(diagnostics).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn open_log(&self, direction: Option<&BufferDirection>) -> Result<()> {
+        for sink in self.sinks.iter() {
+            sink.open_log(direction).await?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for ClientHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientHandle")
+            .field("pid", &self.pid)
+            .field("roots", &self.roots)
+            .finish()
+    }
+}
@@ -0,0 +1,60 @@
+use super::{BuildStatus, ClientSink};
+use crate::Result;
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// Sink writing headless client log to a file, removed when the client is dropped
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    pub async fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientSink for FileSink {
+    async fn append(&self, lines: &[String]) -> Result<()> {
+        let content = lines.iter().map(|l| format!("{l}\n")).collect::<String>();
+        let mut file = self.file.lock().await;
+        file.write_all(content.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.file.lock().await.set_len(0).await?;
+        Ok(())
+    }
+
+    async fn set_status(&self, _status: BuildStatus) -> Result<()> {
+        Ok(())
+    }
+
+    async fn echo_msg(&self, msg: &str) -> Result<()> {
+        self.append(&[msg.to_string()]).await
+    }
+
+    async fn echo_err(&self, msg: &str) -> Result<()> {
+        self.append(&[format!("[Error] {msg}")]).await
+    }
+
+    async fn sync_state(&self, _state: &str) -> Result<()> {
+        Ok(())
+    }
+}
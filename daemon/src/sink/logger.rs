@@ -0,0 +1,156 @@
+use super::{BuildStatus, ClientHandle};
+use crate::build::{BuildEvent, Diagnostic};
+use crate::Result;
+use crate::{util::fmt, BuildStream};
+use futures::StreamExt;
+use xbase_proto::BufferDirection;
+
+pub struct Logger<'a> {
+    pub client: &'a ClientHandle,
+    title: String,
+    key: Option<String>,
+    direction: Option<BufferDirection>,
+}
+
+impl<'a> Logger<'a> {
+    pub fn new(client: &'a ClientHandle) -> Self {
+        Self {
+            client,
+            title: Default::default(),
+            key: None,
+            direction: None,
+        }
+    }
+
+    /// Set logger title
+    pub fn set_title(&mut self, title: String) -> &mut Self {
+        self.title = title;
+        self
+    }
+
+    /// Set request key published with logger events
+    pub fn set_key<S: ToString>(&mut self, key: S) -> &mut Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Clear logger content
+    pub async fn clear_content(&self) -> Result<()> {
+        self.client.clear().await
+    }
+
+    /// Set open direction for logger
+    pub fn set_direction(&mut self, direction: &BufferDirection) -> &mut Self {
+        self.direction = Some(direction.clone());
+        self
+    }
+
+    /// Consume build logs via logging them to client
+    pub async fn consume_build_logs(
+        &mut self,
+        mut stream: BuildStream,
+        clear: bool,
+        open: bool,
+    ) -> Result<bool> {
+        let mut exit_code = None;
+        let mut has_errors = false;
+        let mut diagnostics = vec![];
+        // Currently the buffer direction will be ignored if the buffer is opened already
+        if clear {
+            self.clear_content().await?;
+        }
+
+        // TODO(nvim): build log correct height
+        if open {
+            self.open_win().await?;
+        }
+
+        self.set_running(false).await?;
+
+        while let Some(event) = stream.next().await {
+            match &event {
+                BuildEvent::Exit(code) => {
+                    exit_code = Some(*code);
+                    if event.is_failure() {
+                        self.append(event).await?;
+                    }
+                    continue;
+                }
+                BuildEvent::Error(_) => has_errors = true,
+                _ => {}
+            }
+
+            if let Some(diagnostic) = event.line().and_then(Diagnostic::parse) {
+                diagnostics.push(diagnostic);
+            }
+
+            self.append(event).await?;
+        }
+
+        // NOTE: Fallback to reported errors when the process exit code wasn't received
+        let success = exit_code.map(|code| code == 0).unwrap_or(!has_errors);
+
+        // NOTE: Keep diagnostics of previous builds when this one reported none, e.g. the build
+        // step of a run failing before compiling, unless it succeeded and cleared them all
+        Diagnostic::dedup(&mut diagnostics);
+        if !diagnostics.is_empty() || success {
+            self.client.set_diagnostics(&diagnostics).await?;
+        }
+        self.set_status_end(success, open).await?;
+
+        Ok(success)
+    }
+
+    pub async fn append<S: std::fmt::Display>(&mut self, msg: S) -> Result<()> {
+        log::trace!("{msg}");
+        let leading = if self.title.is_empty() {
+            "".to_string()
+        } else {
+            format!("[{}] ", self.title)
+        };
+
+        let lines = msg
+            .to_string()
+            .split("\n")
+            .map(|s| format!("{leading}{s}"))
+            .collect::<Vec<String>>();
+
+        self.client.append(self.key.as_deref(), &lines).await
+    }
+
+    /// Make logs visible to the user
+    pub async fn open_win(&mut self) -> Result<()> {
+        self.client.open_log(self.direction.as_ref()).await
+    }
+
+    pub async fn set_running(&mut self, is_device: bool) -> Result<()> {
+        if is_device {
+            self.client
+                .set_status(self.key.as_deref(), BuildStatus::DeviceRunning)
+                .await
+        } else {
+            self.client
+                .set_status(self.key.as_deref(), BuildStatus::Running)
+                .await
+        }
+    }
+
+    pub async fn set_status_end(&mut self, success: bool, open: bool) -> Result<()> {
+        if success {
+            self.client
+                .set_status(self.key.as_deref(), BuildStatus::Success)
+                .await?;
+            self.append(fmt::separator()).await?;
+        } else {
+            self.client
+                .set_status(self.key.as_deref(), BuildStatus::Failure)
+                .await?;
+        }
+
+        if open || !success {
+            self.open_win().await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
+use crate::store::RunnerEntry;
 use crate::Result;
+use xbase_proto::{ProjectStatus, Status};
 
 /// Build Server State.
 #[derive(Default, Debug, serde::Serialize)]
@@ -11,6 +13,14 @@ pub struct State {
     pub watcher: crate::store::WatchStore,
     /// Available Devices
     pub devices: crate::store::Devices,
+    /// Test reports
+    pub tests: crate::store::TestStore,
+    /// Running processes
+    #[serde(skip)]
+    pub runners: crate::store::RunnerStore,
+    /// Watch requests of previous daemon instance, pending client registration
+    #[serde(skip)]
+    pub snapshot: crate::store::Snapshot,
 }
 
 impl State {
@@ -20,14 +30,54 @@ impl State {
 
     pub async fn sync_client_state(&self) -> Result<()> {
         let state_str = self.try_into_string()?;
-        let update_state_script = format!("vim.g.xbase= vim.json.decode([[{state_str}]])");
         log::trace!("SYNC CLIENT STATES");
 
-        self.clients.update_state(&update_state_script).await?;
+        self.clients.update_state(&state_str).await?;
+        *crate::constants::DAEMON_STATUS.write().await = self.status();
+
+        if let Err(err) = crate::store::Snapshot::save(self).await {
+            log::error!("Fail to save state snapshot: {err}");
+        }
 
         Ok(())
     }
 
+    /// Get status of projects, watchers, running processes and clients
+    pub fn status(&self) -> StatusCache {
+        let projects = self
+            .projects
+            .iter()
+            .map(|(root, project)| {
+                let mut targets = project.targets().keys().cloned().collect::<Vec<_>>();
+                let mut watchers = self
+                    .watcher
+                    .get(root)
+                    .map(|w| w.listeners.keys().cloned().collect::<Vec<_>>())
+                    .unwrap_or_default();
+                targets.sort();
+                watchers.sort();
+
+                ProjectStatus {
+                    root: root.clone(),
+                    name: project.name().to_string(),
+                    clients: project.clients().clone(),
+                    targets,
+                    watchers,
+                }
+            })
+            .collect();
+
+        StatusCache {
+            projects,
+            runners: self
+                .runners
+                .iter()
+                .map(|(key, entry)| (key.clone(), entry.clone()))
+                .collect(),
+            clients: self.clients.keys().cloned().collect(),
+        }
+    }
+
     pub async fn validate(&mut self) {
         let mut invalid_pids = vec![];
 
@@ -48,3 +98,30 @@ impl State {
         }
     }
 }
+
+/// State status as of its last sync. Kept apart from the state so it can be read while the state
+/// is locked, e.g. during builds.
+#[derive(Debug, Default)]
+pub struct StatusCache {
+    projects: Vec<ProjectStatus>,
+    runners: Vec<(String, RunnerEntry)>,
+    clients: Vec<i32>,
+}
+
+impl StatusCache {
+    /// Get status, with running processes read from their handlers
+    pub async fn status(&self) -> Status {
+        let mut runners = vec![];
+        for (key, entry) in self.runners.iter() {
+            if let Some(status) = entry.status(key).await {
+                runners.push(status);
+            }
+        }
+
+        Status {
+            projects: self.projects.clone(),
+            runners,
+            clients: self.clients.clone(),
+        }
+    }
+}
@@ -2,9 +2,14 @@ mod clients;
 mod devices;
 mod projects;
 mod runners;
+mod snapshot;
+mod tests;
 mod watcher;
 
 pub use clients::ClientStore;
 pub use devices::*;
 pub use projects::ProjectStore;
+pub use runners::{RunnerEntry, RunnerStore};
+pub use snapshot::{Snapshot, WatchRequest};
+pub use tests::TestStore;
 pub use watcher::WatchStore;
@@ -1,4 +1,4 @@
-use crate::nvim::NvimClient;
+use crate::sink::ClientHandle;
 use crate::Result;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -7,11 +7,20 @@ use tap::Pipe;
 use xbase_proto::{Client, IntoResult};
 
 #[derive(Default, Debug, Serialize, derive_deref_rs::Deref)]
-pub struct ClientStore(HashMap<i32, NvimClient>);
+pub struct ClientStore(HashMap<i32, ClientHandle>);
 
 impl ClientStore {
+    /// Add client, or add client root to it when it is already added
     pub async fn add(&mut self, client: &Client) -> Result<()> {
-        NvimClient::new(client)
+        if let Some(handle) = self.0.get_mut(&client.pid) {
+            if !handle.roots.contains(&client.root) {
+                handle.roots.push(client.root.clone());
+                log::info!("[{:?}] added {:?}", client.pid, client.root);
+            }
+            return Ok(());
+        }
+
+        ClientHandle::new(client)
             .await?
             .pipe(|client| self.insert(client.pid, client));
         log::info!("[{:?}] added", client.pid);
@@ -23,19 +32,19 @@ impl ClientStore {
         self.0.remove(&client.pid);
     }
 
-    pub fn get(&self, pid: &i32) -> Result<&NvimClient> {
+    pub fn get(&self, pid: &i32) -> Result<&ClientHandle> {
         let client = self.0.get(&pid).into_result("Client", pid)?;
         log::trace!("[{:?}] accessed", pid);
         Ok(client)
     }
 
-    pub fn get_mut(&mut self, pid: &i32) -> Result<&mut NvimClient> {
+    pub fn get_mut(&mut self, pid: &i32) -> Result<&mut ClientHandle> {
         let client = self.0.get_mut(&pid).into_result("Client", pid)?;
         log::trace!("[{:?}] accessed", pid);
         Ok(client)
     }
 
-    pub async fn get_clients_by_root<'a>(&'a self, root: &'a PathBuf) -> Vec<&'a NvimClient> {
+    pub async fn get_clients_by_root<'a>(&'a self, root: &'a PathBuf) -> Vec<&'a ClientHandle> {
         self.0
             .iter()
             .filter(|(_, client)| client.roots.contains(root))
@@ -43,14 +52,8 @@ impl ClientStore {
             .collect()
     }
 
-    pub async fn log_info(&self, root: &PathBuf, scope: &str, msg: &str) {
-        for client in self.get_clients_by_root(&root).await {
-            client.log_info(scope, msg).await.ok();
-        }
-    }
-
     pub async fn echo_msg(&self, root: &PathBuf, scope: &str, msg: &str) {
-        let msg = format!("echo '{scope}: {msg}'");
+        let msg = format!("{scope}: {msg}");
         for client in self.get_clients_by_root(&root).await {
             client.echo_msg(&msg).await.ok();
         }
@@ -63,15 +66,9 @@ impl ClientStore {
         }
     }
 
-    pub async fn log_error(&self, root: &PathBuf, scope: &str, msg: &str) {
-        for client in self.get_clients_by_root(&root).await {
-            client.log_error(scope, msg).await.ok();
-        }
-    }
-
-    pub async fn update_state(&self, update_state_script: &str) -> Result<()> {
+    pub async fn update_state(&self, state: &str) -> Result<()> {
         for (_, client) in self.iter() {
-            client.sync_state(update_state_script).await?
+            client.sync_state(state).await?
         }
         Ok(())
     }
@@ -1,15 +1,41 @@
 use crate::project::{project, Project};
 use crate::Result;
-use serde::Serialize;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use xbase_proto::Client;
 use xbase_proto::IntoResult;
 
-#[derive(Default, Debug, derive_deref_rs::Deref, Serialize)]
+#[derive(Default, Debug, derive_deref_rs::Deref)]
 pub struct ProjectStore(HashMap<PathBuf, Box<dyn Project + Send>>);
 
-// TODO(projects): presist a list of projects paths and information
+/// Project state published to clients, with schemes resolved for each target
+#[derive(Serialize)]
+struct ProjectState<'a> {
+    #[serde(flatten)]
+    project: &'a Box<dyn Project + Send>,
+    target_schemes: HashMap<String, String>,
+    /// Targets defined by more than one project, built by name with the first one only
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    shadowed_targets: HashMap<String, Vec<PathBuf>>,
+}
+
+impl Serialize for ProjectStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (root, project) in self.0.iter() {
+            let state = ProjectState {
+                project,
+                target_schemes: project.target_schemes(),
+                shadowed_targets: project.shadowed_targets(),
+            };
+            map.serialize_entry(root, &state)?;
+        }
+        map.end()
+    }
+}
+
 impl ProjectStore {
     pub async fn add(&mut self, client: &Client) -> Result<()> {
         let key = client.root.to_path_buf();
@@ -34,6 +60,23 @@ impl ProjectStore {
         Ok(project)
     }
 
+    /// Get root of the nearest project enclosing given path
+    pub fn nearest_root(&self, path: &Path) -> Option<&PathBuf> {
+        self.0
+            .keys()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+    }
+
+    /// Get roots of projects registered under given root by client pid, including root itself
+    pub fn nested_roots(&self, root: &Path, pid: &i32) -> Vec<PathBuf> {
+        self.0
+            .iter()
+            .filter(|(key, project)| key.starts_with(root) && project.clients().contains(pid))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
     /// Remove project using root and pid.
     ///
     /// if pid doesn't exists in Project.clients the remove aborts,
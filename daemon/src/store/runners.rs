@@ -1,10 +1,73 @@
-// use crate::daemon::WatchTarget;
-// use crate::watcher::WatchHandler;
+use crate::run::RunServiceHandler;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use xbase_proto::RunnerStatus;
 
-// use crate::types::{Client, Root};
-// use serde::{Deserialize, Serialize};
-// use std::collections::HashMap;
-// use std::path::PathBuf;
+/// Running process started through a RunRequest
+#[derive(Clone)]
+pub struct RunnerEntry {
+    pub root: PathBuf,
+    pub target: String,
+    pub device: Option<String>,
+    pub handler: Arc<Mutex<Option<RunServiceHandler>>>,
+}
 
-// #[derive(Default, Debug, Deserialize, Serialize)]
-// pub struct RunnersStore(HashMap<>)
+impl std::fmt::Debug for RunnerEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunnerEntry")
+            .field("root", &self.root)
+            .field("target", &self.target)
+            .field("device", &self.device)
+            .finish()
+    }
+}
+
+#[derive(Default, Debug, derive_deref_rs::Deref)]
+pub struct RunnerStore(HashMap<String, RunnerEntry>);
+
+impl RunnerStore {
+    pub fn insert(&mut self, key: &str, entry: RunnerEntry) {
+        // NOTE: Remove runners that are no longer running
+        self.0.retain(|_, entry| {
+            entry
+                .handler
+                .try_lock()
+                .map(|h| h.as_ref().map_or(true, |h| !h.inner().is_finished()))
+                .unwrap_or(true)
+        });
+
+        log::info!("[{key}] added");
+        self.0.insert(key.to_string(), entry);
+    }
+
+    pub fn remove_by_root(&mut self, root: &PathBuf) {
+        self.0.retain(|_, entry| &entry.root != root);
+    }
+}
+
+impl RunnerEntry {
+    /// Get status of the process, None when it is no longer or not yet running
+    pub async fn status(&self, key: &str) -> Option<RunnerStatus> {
+        let mut status = RunnerStatus {
+            key: key.to_string(),
+            root: self.root.clone(),
+            target: self.target.clone(),
+            device: self.device.clone(),
+            ..RunnerStatus::default()
+        };
+
+        // NOTE: The handler is locked while the process is rebuilt and restarted
+        if let Ok(handler) = self.handler.try_lock() {
+            let handler = handler.as_ref()?;
+            if handler.inner().is_finished() {
+                return None;
+            }
+            status.pid = handler.pid();
+            status.uptime = handler.started().elapsed().unwrap_or_default().as_secs();
+        }
+
+        Some(status)
+    }
+}
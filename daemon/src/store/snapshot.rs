@@ -0,0 +1,143 @@
+use crate::constants::{DAEMON_SNAPSHOT_PATH, DAEMON_STATE};
+use crate::run::RunService;
+use crate::state::State;
+use crate::util::pid;
+use crate::{RequestHandler, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::MutexGuard;
+use xbase_proto::{BuildRequest, Client, RegisterRequest, RunRequest};
+
+/// Request used to create a watch listener, kept to recreate it after daemon restarts.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "request")]
+pub enum WatchRequest {
+    Build(BuildRequest),
+    Run(RunRequest),
+}
+
+impl WatchRequest {
+    /// Recreate watch listener for a newly registered client
+    pub async fn restore(self, state: &mut MutexGuard<'_, State>, client: &Client) -> Result<()> {
+        match self {
+            Self::Build(mut req) => {
+                req.client = client.clone();
+                log::info!("[{req}] restored");
+                state.watcher.get_mut(&client.root)?.add(req)?;
+            }
+            Self::Run(mut req) => {
+                req.client = client.clone();
+                log::info!("[{req}] restored");
+                // NOTE: Restored runs are launched again on the next change only
+                let run_service = RunService::restore(state, req);
+                state.watcher.get_mut(&client.root)?.add(run_service)?;
+            }
+        }
+
+        state.clients.get(&client.pid)?.set_watching(true).await?;
+
+        Ok(())
+    }
+}
+
+/// Registered clients with their settings and project watch requests, persisted across daemon
+/// restarts.
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Register requests of connected clients, replayed on daemon startup
+    #[serde(default)]
+    registrations: Vec<RegisterRequest>,
+    /// Watch requests by project root, restored once the project is registered again
+    #[serde(default)]
+    requests: HashMap<PathBuf, Vec<WatchRequest>>,
+}
+
+impl Snapshot {
+    /// Load snapshot saved by a previous daemon instance
+    pub fn load() -> Self {
+        let snapshot = std::fs::read_to_string(&*DAEMON_SNAPSHOT_PATH)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .unwrap_or_default();
+
+        if !snapshot.registrations.is_empty() {
+            log::info!(
+                "Loaded snapshot of {} registration(s)",
+                snapshot.registrations.len()
+            );
+        }
+
+        snapshot
+    }
+
+    /// Register again clients of previous daemon instance that are still running, restoring
+    /// their projects, settings and watchers.
+    pub async fn rehydrate() {
+        let registrations = {
+            let state = DAEMON_STATE.clone();
+            let mut state = state.lock().await;
+            std::mem::take(&mut state.snapshot.registrations)
+        };
+
+        for req in registrations {
+            if !pid::exists(&req.client.pid, || {}) {
+                continue;
+            }
+
+            log::info!("[{:?}] rehydrating {:?}", req.client.pid, req.client.root);
+            if let Err(err) = req.handle().await {
+                log::error!("Fail to rehydrate {:?}: {err}", req.client.root);
+            }
+        }
+    }
+
+    /// Keep register request of a client to replay it after daemon restarts
+    pub fn add_registration(&mut self, req: &RegisterRequest) {
+        let Client { pid, root, .. } = &req.client;
+        self.registrations
+            .retain(|r| &r.client.pid != pid || &r.client.root != root);
+        self.registrations.push(req.clone());
+    }
+
+    /// Take pending watch requests of a given project root
+    pub fn take(&mut self, root: &PathBuf) -> Vec<WatchRequest> {
+        self.requests.remove(root).unwrap_or_default()
+    }
+
+    /// Save current state merged with not yet restored watch requests
+    pub async fn save(state: &State) -> Result<()> {
+        let mut requests = state.snapshot.requests.clone();
+
+        for (root, _) in state.projects.iter() {
+            let root_requests = state
+                .watcher
+                .get(root)
+                .map(|w| w.listeners.values().flat_map(|l| l.request()).collect())
+                .unwrap_or_default();
+            requests.insert(root.clone(), root_requests);
+        }
+
+        // NOTE: Registrations of dropped clients are not replayed
+        let registrations = state
+            .snapshot
+            .registrations
+            .iter()
+            .filter(|req| state.clients.contains_key(&req.client.pid))
+            .cloned()
+            .collect();
+
+        let snapshot = Self {
+            registrations,
+            requests,
+        };
+
+        let path = &*DAEMON_SNAPSHOT_PATH;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, serde_json::to_vec(&snapshot)?).await?;
+
+        Ok(())
+    }
+}
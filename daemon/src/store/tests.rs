@@ -0,0 +1,21 @@
+use crate::test::TestReport;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Last test report of each target, grouped by project root
+#[derive(Default, Debug, Serialize, derive_deref_rs::Deref)]
+pub struct TestStore(HashMap<PathBuf, HashMap<String, TestReport>>);
+
+impl TestStore {
+    pub fn insert(&mut self, root: &PathBuf, report: TestReport) {
+        self.0
+            .entry(root.clone())
+            .or_default()
+            .insert(report.target.clone(), report);
+    }
+
+    pub fn remove(&mut self, root: &PathBuf) {
+        self.0.remove(root);
+    }
+}
@@ -0,0 +1,80 @@
+mod report;
+
+use crate::constants::DAEMON_STATE;
+use crate::Error;
+use crate::RequestHandler;
+use crate::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::{Arc, Mutex};
+use xbase_proto::TestRequest;
+
+pub use report::*;
+
+#[async_trait]
+impl RequestHandler for TestRequest {
+    async fn handle(self) -> Result<()>
+    where
+        Self: Sized + std::fmt::Debug,
+    {
+        let state = DAEMON_STATE.clone();
+        let ref mut state = state.lock().await;
+
+        let (title, sep) = crate::util::handler_log_content("Test", &self.client);
+        log::info!("{sep}");
+        log::info!("{title}");
+        log::trace!("\n\n{:#?}\n", &self);
+        log::info!("{sep}");
+
+        let (root, config) = (&self.client.root, &self.settings);
+        let device = state.devices.from_lookup(self.device.clone());
+        let (stream, args) = state.projects.get(root)?.test(config, device.as_ref())?;
+        let report = Arc::new(Mutex::new(TestReport::new(&config.target)));
+        let stream = {
+            let report = report.clone();
+            stream
+                .inspect(move |event| {
+                    if let Some(line) = event.line() {
+                        report.lock().unwrap().consume(line)
+                    }
+                })
+                .boxed()
+        };
+
+        let success = {
+            let nvim = state.clients.get(&self.client.pid)?;
+            let logger = &mut nvim.logger();
+
+            logger.set_key(self);
+            logger.set_title(format!("Test:{}", config.target));
+            logger.set_direction(&self.direction);
+
+            log::info!("[target: {}] testing .....", config.target);
+            let success = logger.consume_build_logs(stream, true, true).await?;
+            let summary = report.lock().unwrap().summary();
+            logger.append(&summary).await?;
+            log::info!("[target: {}] {summary}", config.target);
+
+            if !success {
+                let ref msg = format!("Failed: {} ", config.to_string());
+                nvim.echo_err(msg).await?;
+                log::error!("[target: {}] tests failed", config.target);
+                log::error!("[ran: '{}']", args.join(" "));
+            } else {
+                log::info!("[target: {}] tests passed", config.target);
+            };
+
+            success
+        };
+
+        let report = std::mem::take(&mut *report.lock().unwrap());
+        state.tests.insert(root, report);
+        state.sync_client_state().await?;
+
+        if !success {
+            return Err(Error::Build(format!("{} tests failed", config.target)));
+        }
+
+        Ok(())
+    }
+}
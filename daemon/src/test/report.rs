@@ -0,0 +1,274 @@
+//! Parse XCTest and swift-testing output into structured test results.
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Test case result status
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// Test case failure location and message
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct TestFailure {
+    pub file: PathBuf,
+    pub line: u32,
+    pub message: String,
+}
+
+/// Single test case result
+#[derive(Clone, Debug, Serialize)]
+pub struct TestCase {
+    pub suite: String,
+    pub name: String,
+    pub status: TestStatus,
+    /// Duration in seconds
+    pub duration: Option<f64>,
+    pub failures: Vec<TestFailure>,
+}
+
+/// Test results of a given target
+#[derive(Clone, Default, Debug, Serialize)]
+pub struct TestReport {
+    pub target: String,
+    pub cases: Vec<TestCase>,
+    /// Failures reported before their test case finishes.
+    #[serde(skip)]
+    pending: Vec<(String, TestFailure)>,
+    /// Last started swift-testing suite
+    #[serde(skip)]
+    current_suite: String,
+}
+
+impl TestReport {
+    pub fn new(target: &str) -> Self {
+        Self {
+            target: target.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Consume a line of test output, updating the report when the line is relevant.
+    pub fn consume(&mut self, line: &str) {
+        let line = line.trim();
+        if let Some((name, failure)) = parse_xctest_failure(line) {
+            self.pending.push((name, failure));
+        } else if let Some((suite, name, status, duration)) = parse_xctest_case(line) {
+            self.push(suite, name, status, duration);
+        } else if let Some(suite) = parse_swift_testing_suite(line) {
+            self.current_suite = suite;
+        } else if let Some((name, failure)) = parse_swift_testing_issue(line) {
+            self.pending.push((name, failure));
+        } else if let Some((name, status, duration)) = parse_swift_testing_case(line) {
+            self.push(self.current_suite.clone(), name, status, duration);
+        }
+    }
+
+    fn push(&mut self, suite: String, name: String, status: TestStatus, duration: Option<f64>) {
+        let (failures, pending) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(case, _)| case == &name);
+
+        self.pending = pending;
+        self.cases.push(TestCase {
+            suite,
+            name,
+            status,
+            duration,
+            failures: failures.into_iter().map(|(_, f)| f).collect(),
+        });
+    }
+
+    /// Get failed test cases
+    pub fn failed(&self) -> Vec<&TestCase> {
+        self.cases
+            .iter()
+            .filter(|c| c.status == TestStatus::Failed)
+            .collect()
+    }
+
+    /// Get a summary line of the report
+    pub fn summary(&self) -> String {
+        let total = self.cases.len();
+        let failed = self.failed().len();
+        let skipped = self
+            .cases
+            .iter()
+            .filter(|c| c.status == TestStatus::Skipped)
+            .count();
+        let duration: f64 = self.cases.iter().flat_map(|c| c.duration).sum();
+
+        format!(
+            "Executed {total} tests, with {failed} failures and {skipped} skipped in {duration:.3} seconds"
+        )
+    }
+}
+
+/// Split `-[Module.Suite testName]` or `Module.Suite.testName` into suite and test name.
+fn split_case_identifier(identifier: &str) -> Option<(String, String)> {
+    let identifier = identifier.trim();
+    let (suite, name) = if let Some(inner) = identifier
+        .strip_prefix("-[")
+        .and_then(|s| s.strip_suffix(']'))
+    {
+        inner.split_once(' ')?
+    } else {
+        identifier.rsplit_once('.')?
+    };
+    let suite = suite.rsplit('.').next().unwrap_or(suite);
+
+    Some((suite.to_string(), name.to_string()))
+}
+
+/// Parse duration from `(0.003 seconds)` or `0.003 seconds`
+fn parse_duration(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .trim_start_matches('(')
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Parse `Test Case '-[Module.Suite testName]' passed (0.003 seconds).`
+fn parse_xctest_case(line: &str) -> Option<(String, String, TestStatus, Option<f64>)> {
+    let rest = line.strip_prefix("Test Case '")?;
+    let (identifier, rest) = rest.split_once("' ")?;
+    let (status, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+    let status = match status.trim_end_matches('.') {
+        "passed" => TestStatus::Passed,
+        "failed" => TestStatus::Failed,
+        "skipped" => TestStatus::Skipped,
+        _ => return None,
+    };
+    let (suite, name) = split_case_identifier(identifier)?;
+
+    Some((suite, name, status, parse_duration(rest)))
+}
+
+/// Parse `/path/File.swift:12: error: -[Module.Suite testName] : message`
+fn parse_xctest_failure(line: &str) -> Option<(String, TestFailure)> {
+    let (location, rest) = line.split_once(": error: ")?;
+    let (file, line_number) = location.rsplit_once(':')?;
+    let (identifier, message) = rest.split_once(" : ")?;
+    let (_, name) = split_case_identifier(identifier)?;
+
+    Some((
+        name,
+        TestFailure {
+            file: file.into(),
+            line: line_number.parse().ok()?,
+            message: message.trim().to_string(),
+        },
+    ))
+}
+
+/// Parse `◇ Suite FooSuite started.`
+fn parse_swift_testing_suite(line: &str) -> Option<String> {
+    let (_, rest) = line.split_once("Suite ")?;
+    let name = rest.strip_suffix(" started.")?;
+
+    Some(name.trim_matches('"').to_string())
+}
+
+/// Parse `✘ Test testFoo() recorded an issue at FooTests.swift:12:5: Expectation failed: ...`
+fn parse_swift_testing_issue(line: &str) -> Option<(String, TestFailure)> {
+    let (_, rest) = line.split_once("Test ")?;
+    let (name, rest) = rest.split_once(" recorded an issue at ")?;
+    let (location, message) = rest.split_once(": ")?;
+    let mut location = location.split(':');
+    let file = location.next()?;
+    let line_number = location.next()?.parse().ok()?;
+
+    Some((
+        name.trim_matches('"').to_string(),
+        TestFailure {
+            file: file.into(),
+            line: line_number,
+            message: message.trim().to_string(),
+        },
+    ))
+}
+
+/// Parse `✔ Test testFoo() passed after 0.001 seconds.`
+fn parse_swift_testing_case(line: &str) -> Option<(String, TestStatus, Option<f64>)> {
+    let (_, rest) = line.split_once("Test ")?;
+    if let Some(name) = rest.strip_suffix(" skipped.") {
+        return Some((
+            name.trim_matches('"').to_string(),
+            TestStatus::Skipped,
+            None,
+        ));
+    }
+
+    let (name, status, rest) = if let Some((name, rest)) = rest.split_once(" passed after ") {
+        (name, TestStatus::Passed, rest)
+    } else if let Some((name, rest)) = rest.split_once(" failed after ") {
+        (name, TestStatus::Failed, rest)
+    } else {
+        return None;
+    };
+
+    Some((
+        name.trim_matches('"').to_string(),
+        status,
+        parse_duration(rest),
+    ))
+}
+
+#[test]
+fn test_xctest_report() {
+    let mut report = TestReport::new("FooTests");
+    let output = r#"
+Test Suite 'FooTests' started at 2022-07-01 10:00:00.000
+Test Case '-[FooTests.FooTests testBar]' started.
+Test Case '-[FooTests.FooTests testBar]' passed (0.003 seconds).
+Test Case '-[FooTests.FooTests testBaz]' started.
+/tmp/Foo/Tests/FooTests/FooTests.swift:12: error: -[FooTests.FooTests testBaz] : XCTAssertEqual failed: ("1") is not equal to ("2")
+Test Case '-[FooTests.FooTests testBaz]' failed (0.010 seconds).
+Test Case 'FooTests.testQux' skipped (0.001 seconds).
+"#;
+    output.lines().for_each(|line| report.consume(line));
+
+    assert_eq!(report.cases.len(), 3);
+    assert_eq!(report.cases[0].suite, "FooTests");
+    assert_eq!(report.cases[0].name, "testBar");
+    assert_eq!(report.cases[0].status, TestStatus::Passed);
+    assert_eq!(report.cases[0].duration, Some(0.003));
+    assert_eq!(report.cases[1].status, TestStatus::Failed);
+    assert_eq!(
+        report.cases[1].failures,
+        vec![TestFailure {
+            file: "/tmp/Foo/Tests/FooTests/FooTests.swift".into(),
+            line: 12,
+            message: r#"XCTAssertEqual failed: ("1") is not equal to ("2")"#.into()
+        }]
+    );
+    assert_eq!(report.cases[2].name, "testQux");
+    assert_eq!(report.cases[2].status, TestStatus::Skipped);
+    assert_eq!(report.failed().len(), 1);
+}
+
+#[test]
+fn test_swift_testing_report() {
+    let mut report = TestReport::new("FooTests");
+    let output = r#"
+◇ Suite FooSuite started.
+◇ Test foo() started.
+✔ Test foo() passed after 0.001 seconds.
+✘ Test bar() recorded an issue at FooTests.swift:21:5: Expectation failed: (a → 1) == 2
+✘ Test bar() failed after 0.002 seconds with 1 issue.
+"#;
+    output.lines().for_each(|line| report.consume(line));
+
+    assert_eq!(report.cases.len(), 2);
+    assert_eq!(report.cases[0].suite, "FooSuite");
+    assert_eq!(report.cases[0].name, "foo()");
+    assert_eq!(report.cases[1].status, TestStatus::Failed);
+    assert_eq!(report.cases[1].duration, Some(0.002));
+    assert_eq!(report.cases[1].failures[0].line, 21);
+}
@@ -49,6 +49,11 @@ pub fn get_build_cache_dir_with_config<P: AsRef<Path> + Debug>(
     _get_build_cache_dir(root_path, Some(config))
 }
 
+/// Get path to where client logs will be written
+pub fn get_client_log_path(pid: &i32) -> std::path::PathBuf {
+    Path::new(crate::constants::CLIENT_LOG_ROOT).join(format!("{pid}.log"))
+}
+
 /// Get path to binary by name
 pub fn which(cmd: &str) -> Result<String> {
     Ok(which::which(cmd)?.to_str().unwrap().to_string())
@@ -127,3 +132,18 @@ pub fn abbrv_path<P: AsRef<Path>>(path: P) -> String {
 
     abbr().unwrap_or_default()
 }
+
+/// Lexically normalize path, resolving `.` and `..` components without touching the filesystem
+pub fn normalize(path: &Path) -> std::path::PathBuf {
+    let mut normalized = std::path::PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
@@ -2,7 +2,9 @@
 
 pub mod fmt;
 pub mod fs;
+pub mod pbxproj;
 pub mod pid;
+pub mod xml;
 
 use crate::OutputStream;
 use process_stream::{ProcessItem, StreamExt};
@@ -0,0 +1,192 @@
+//! Minimal reader of `project.pbxproj` property lists, for data the xcodeproj crate doesn't expose
+use std::collections::HashMap;
+
+/// Property list value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Array(Vec<Value>),
+    Object(HashMap<String, Value>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Self::Array(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&HashMap<String, Value>> {
+        match self {
+            Self::Object(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get object value at given key
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object()?.get(key)
+    }
+
+    /// Get object string value at given key
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key)?.as_str()
+    }
+}
+
+/// Parse old-style property list content, e.g. `project.pbxproj`
+pub fn parse(content: &str) -> Option<Value> {
+    let mut tokens = tokens(content).into_iter().peekable();
+    value(&mut tokens)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Symbol(char),
+    String(String),
+}
+
+type Tokens = std::iter::Peekable<std::vec::IntoIter<Token>>;
+
+fn value(tokens: &mut Tokens) -> Option<Value> {
+    match tokens.next()? {
+        Token::String(value) => Some(Value::String(value)),
+        Token::Symbol('{') => {
+            let mut object = HashMap::new();
+            loop {
+                let key = match tokens.next()? {
+                    Token::Symbol('}') => return Some(Value::Object(object)),
+                    Token::String(key) => key,
+                    _ => return None,
+                };
+                symbol(tokens, '=')?;
+                object.insert(key, value(tokens)?);
+                symbol(tokens, ';')?;
+            }
+        }
+        Token::Symbol('(') => {
+            let mut array = vec![];
+            loop {
+                if tokens.peek()? == &Token::Symbol(')') {
+                    tokens.next();
+                    return Some(Value::Array(array));
+                }
+                array.push(value(tokens)?);
+                if tokens.peek()? == &Token::Symbol(',') {
+                    tokens.next();
+                }
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Consume given symbol, returning None when the next token is anything else
+fn symbol(tokens: &mut Tokens, symbol: char) -> Option<()> {
+    match tokens.next()? {
+        Token::Symbol(c) if c == symbol => Some(()),
+        _ => None,
+    }
+}
+
+/// Split content into symbols and strings, skipping comments
+fn tokens(content: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                while let Some(c) = chars.next() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while let Some(c) = chars.next() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '{' | '}' | '(' | ')' | '=' | ';' | ',' => tokens.push(Token::Symbol(c)),
+            '"' => {
+                let mut value = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(c) => value.push(c),
+                            None => break,
+                        },
+                        c => value.push(c),
+                    }
+                }
+                tokens.push(Token::String(value));
+            }
+            c => {
+                let mut value = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "{}()=;,\"".contains(c) {
+                        break;
+                    }
+                    value.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::String(value));
+            }
+        }
+    }
+
+    tokens
+}
+
+#[test]
+fn test_parse_pbxproj() {
+    let content = r#"// !$*UTF8*$!
+{
+	archiveVersion = 1;
+	objects = {
+		A1 /* App */ = {
+			isa = PBXGroup;
+			children = (
+				B1 /* App.swift */,
+			);
+			path = "My App";
+			sourceTree = "<group>";
+		};
+		B1 /* App.swift */ = {isa = PBXFileReference; path = App.swift; sourceTree = "<group>"; };
+	};
+	rootObject = C1 /* Project object */;
+}"#;
+
+    let plist = parse(content).unwrap();
+    let objects = plist.get("objects").unwrap();
+    let group = objects.get("A1").unwrap();
+
+    assert_eq!(plist.get_str("rootObject"), Some("C1"));
+    assert_eq!(group.get_str("path"), Some("My App"));
+    assert_eq!(
+        group.get("children"),
+        Some(&Value::Array(vec![Value::String("B1".into())]))
+    );
+    assert_eq!(
+        objects.get("B1").unwrap().get_str("path"),
+        Some("App.swift")
+    );
+}
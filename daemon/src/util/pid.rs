@@ -1,4 +1,4 @@
-use std::{ffi::OsStr, fmt::Display, string::String};
+use std::{ffi::OsStr, fmt::Display, path::Path, string::String};
 
 /// Kill process using kill command
 pub async fn kill(pid_str: &String) -> anyhow::Result<bool> {
@@ -47,6 +47,28 @@ where
     Err(crate::Error::Lookup("Process".into(), format!("{name}")))
 }
 
+/// Get pid of a process spawned by the daemon running the executable at given path.
+pub fn get_child_by_path(path: &Path) -> Option<u32> {
+    use libproc::libproc::{bsd_info::BSDInfo, proc_pid};
+
+    let daemon_pid = std::process::id();
+    let path = path.canonicalize().ok()?;
+
+    proc_pid::listpids(proc_pid::ProcType::ProcAllPIDS)
+        .ok()?
+        .into_iter()
+        .filter(|pid| {
+            proc_pid::pidinfo::<BSDInfo>(*pid as i32, 0)
+                .map(|info| info.pbi_ppid == daemon_pid)
+                .unwrap_or_default()
+        })
+        .find(|pid| {
+            proc_pid::pidpath(*pid as i32)
+                .map(|child| Path::new(&child) == path)
+                .unwrap_or_default()
+        })
+}
+
 #[test]
 fn test_get_by_name() {
     let existing_process = get_by_name("DockHelper");
@@ -0,0 +1,64 @@
+//! Minimal xml tags reader for Xcode xml files such as schemes and workspaces
+use std::collections::HashMap;
+
+/// Xml element tag
+#[derive(Debug)]
+pub struct Tag {
+    pub name: String,
+    pub attributes: HashMap<String, String>,
+    pub is_closing: bool,
+    pub is_self_closing: bool,
+}
+
+/// Iterate xml element tags, skipping declarations and comments
+pub fn tags(content: &str) -> impl Iterator<Item = Tag> + '_ {
+    content.split('<').skip(1).filter_map(|segment| {
+        let (inner, _) = segment.split_once('>')?;
+        if inner.starts_with('?') || inner.starts_with('!') {
+            return None;
+        }
+
+        let is_closing = inner.starts_with('/');
+        let is_self_closing = inner.ends_with('/');
+        let inner = inner.trim_start_matches('/').trim_end_matches('/').trim();
+        let (name, rest) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+
+        Some(Tag {
+            name: name.to_string(),
+            attributes: attributes(rest),
+            is_closing,
+            is_self_closing,
+        })
+    })
+}
+
+/// Parse `key = "value"` attribute pairs
+fn attributes(mut content: &str) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+
+    while let Some((key, rest)) = content.split_once('=') {
+        let rest = rest.trim_start();
+        let quote = match rest.chars().next() {
+            Some(c @ ('"' | '\'')) => c,
+            _ => break,
+        };
+        let (value, rest) = match rest[1..].split_once(quote) {
+            Some(pair) => pair,
+            None => break,
+        };
+        attributes.insert(key.trim().to_string(), unescape(value));
+        content = rest;
+    }
+
+    attributes
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
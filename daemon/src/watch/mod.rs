@@ -4,18 +4,25 @@ mod serialize;
 pub use event::{Event, EventKind};
 
 use crate::compile::ensure_server_support;
+use crate::store::WatchRequest;
 use crate::{constants::DAEMON_STATE, state::State, Result};
 use async_trait::async_trait;
 use log::{error, info, trace};
 use notify::{Config, RecommendedWatcher, RecursiveMode::Recursive, Watcher};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tokio::sync::mpsc::channel;
 use tokio::{sync::MutexGuard, task::JoinHandle};
 use xbase_proto::{Client, IntoResult};
 
+/// Time without new events after which a burst of events is handled, e.g. `git checkout`
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(300);
+
+/// Maximum time events are collected into a single burst
+const MAX_BURST_DURATION: Duration = Duration::from_secs(5);
+
 #[derive(derive_deref_rs::Deref)]
 pub struct WatchService {
     #[deref]
@@ -44,6 +51,11 @@ pub trait Watchable: ToString + Send + Sync + 'static {
 
     /// Drop watchable for watching a given file system
     async fn discard(&self, state: &MutexGuard<State>) -> Result<()>;
+
+    /// Request used to create the watchable, used to restore it after daemon restarts
+    fn request(&self) -> Option<WatchRequest> {
+        None
+    }
 }
 
 impl WatchService {
@@ -51,17 +63,19 @@ impl WatchService {
         let listeners = Default::default();
 
         async fn try_to_recompile<'a>(
-            event: &Event,
+            events: &[Event],
             client: &Client,
             state: &mut MutexGuard<'a, State>,
         ) -> Result<()> {
-            let recompiled = event.is_create_event()
-                || event.is_remove_event()
-                || event.is_content_update_event()
-                || event.is_rename_event() && !event.is_seen();
+            let recompiled = events.iter().any(|event| {
+                event.is_create_event()
+                    || event.is_remove_event()
+                    || event.is_content_update_event()
+                    || event.is_rename_event() && !event.is_seen()
+            });
 
             if recompiled {
-                let ensure = ensure_server_support(state, client, Some(event)).await;
+                let ensure = ensure_server_support(state, client, events).await;
                 match ensure {
                     Err(err) => {
                         log::error!("Ensure server support Errored!! {err:?} ");
@@ -105,55 +119,91 @@ impl WatchService {
 
             let ignore = wax::any::<wax::Glob, _>(ignore_pattern).unwrap();
 
-            while let Some(event) = rx.recv().await {
-                let ref event = match Event::new(&ignore, &internal_state, event) {
-                    Some(e) => e,
-                    None => continue,
-                };
+            'watch: while let Some(event) = rx.recv().await {
+                let mut events = vec![event];
+                let deadline = tokio::time::Instant::now() + MAX_BURST_DURATION;
+                while tokio::time::Instant::now() < deadline {
+                    match tokio::time::timeout(DEBOUNCE_WINDOW, rx.recv()).await {
+                        Ok(Some(event)) => events.push(event),
+                        _ => break,
+                    }
+                }
+
+                let events = events
+                    .into_iter()
+                    .filter_map(|event| Event::new(&ignore, &internal_state, event))
+                    .filter(|event| {
+                        // IGNORE EVENTS OF RENAME FOR PATHS THAT NO LONGER EXISTS
+                        let is_gone = !event.path().exists() && event.is_rename_event();
+                        if is_gone {
+                            log::debug!("{} [ignored]", event);
+                        }
+                        !is_gone
+                    })
+                    .collect::<Vec<_>>();
 
-                // IGNORE EVENTS OF RENAME FOR PATHS THAT NO LONGER EXISTS
-                if !event.path().exists() && event.is_rename_event() {
-                    log::debug!("{} [ignored]", event);
+                if events.is_empty() {
                     continue;
                 }
 
                 let state = DAEMON_STATE.clone();
                 let ref mut state = state.lock().await;
 
-                try_to_recompile(event, &client, state).await?;
+                // NOTE: Events under nested projects are handled by their own watcher only
+                let events = events
+                    .into_iter()
+                    .filter(|event| state.projects.nearest_root(event.path()) == Some(root))
+                    .collect::<Vec<_>>();
 
-                let watcher = match state.watcher.get(root) {
-                    Ok(w) => w,
-                    Err(err) => {
-                        error!(r#"Unable to get watcher for {root:?}: {err}"#);
-                        info!(r#"Dropping watcher for {root:?}: {err}"#);
-                        break;
-                    }
-                };
+                if events.is_empty() {
+                    continue;
+                }
+
+                // NOTE: The project is regenerated at most once per burst, with every event
+                // that requires it
+                try_to_recompile(&events, &client, state).await?;
+
+                // NOTE: Listeners are triggered at most once per burst, on the first event
+                // they accept
+                let mut triggered = HashSet::new();
+                for event in events.iter() {
+                    let watcher = match state.watcher.get(root) {
+                        Ok(w) => w,
+                        Err(err) => {
+                            error!(r#"Unable to get watcher for {root:?}: {err}"#);
+                            info!(r#"Dropping watcher for {root:?}: {err}"#);
+                            break 'watch;
+                        }
+                    };
 
-                for (key, listener) in watcher.listeners.iter() {
-                    if listener.should_discard(state, event).await {
-                        if let Err(err) = listener.discard(state).await {
-                            error!(" discard errored for `{key}`!: {err}");
+                    for (key, listener) in watcher.listeners.iter() {
+                        if triggered.contains(key) {
+                            continue;
                         }
-                        discards.push(key.to_string());
-                    } else if listener.should_trigger(state, event).await {
-                        if let Err(err) = listener.trigger(state, event).await {
-                            error!("trigger errored for `{key}`!: {err}");
+                        if listener.should_discard(state, event).await {
+                            if let Err(err) = listener.discard(state).await {
+                                error!(" discard errored for `{key}`!: {err}");
+                            }
+                            discards.push(key.to_string());
+                        } else if listener.should_trigger(state, event).await {
+                            if let Err(err) = listener.trigger(state, event).await {
+                                error!("trigger errored for `{key}`!: {err}");
+                            }
+                            triggered.insert(key.to_string());
                         }
                     }
-                }
-                let watcher = state.watcher.get_mut(root).unwrap();
+                    let watcher = state.watcher.get_mut(root).unwrap();
+
+                    for key in discards.iter() {
+                        info!("[{key:?}] discarded");
+                        watcher.listeners.remove(key);
+                    }
 
-                for key in discards.iter() {
-                    info!("[{key:?}] discarded");
-                    watcher.listeners.remove(key);
+                    discards.clear();
+                    info!("{event} consumed successfully");
                 }
 
-                discards.clear();
                 internal_state.update_debounce();
-
-                info!("{event} consumed successfully");
             }
 
             info!("Dropped {:?}!!", client.root);
@@ -70,12 +70,27 @@ fn ensure(lua: &Lua) -> Result<()> {
 struct DaemonClient;
 
 impl LuaUserData for DaemonClient {
+    fn add_fields<'lua, F: LuaUserDataFields<'lua, Self>>(fields: &mut F) {
+        fields.add_field_method_get("discovery_depth", |_, _| Ok(DISCOVERY_DEPTH));
+    }
+
     fn add_methods<'lua, M: LuaUserDataMethods<'lua, Self>>(methods: &mut M) {
         methods.add_async_function("register", |lua, root: Option<String>| async move {
             ensure(lua)?;
             let client = client();
+            let compile_swift_package = lua
+                .load("return require'xbase.config'.values.swift_package_compile_database")
+                .eval::<Option<bool>>()?
+                .unwrap_or_default();
+            let swift_toolchain = lua
+                .load("return require'xbase.config'.values.swift_toolchain")
+                .eval::<Option<String>>()?
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from);
             let req = RegisterRequest {
                 client: Client::new(lua, root)?,
+                compile_swift_package,
+                swift_toolchain,
             };
 
             let _path = spawn!({ client.register(context::current(), req) }).await??;
@@ -101,6 +116,14 @@ impl LuaUserData for DaemonClient {
             Ok(())
         });
 
+        methods.add_async_function("test", |_, req: TestRequest| async move {
+            let client = client();
+            let ctx = context::current();
+            let _path = spawn!({ client.test(ctx, req) }).await??;
+
+            Ok(())
+        });
+
         methods.add_async_function("drop", |lua, root: Option<String>| async move {
             let client = client();
             let ctx = context::current();
@@ -11,6 +11,13 @@ local defaults = {
   log_level = "debug",
   --- Default log buffer direction: { "horizontal", "vertical", "float" }
   default_log_buffer_direction = "horizontal",
+  --- Generate compile database for swift packages from `swift build -v` and serve them with
+  --- xbase build server instead of sourcekit-lsp own swift package support.
+  swift_package_compile_database = false,
+  --- Path to swift binary used for swift packages, e.g. `<toolchain>/usr/bin/swift` or a
+  --- swiftly shim. When empty, it's resolved from project `.swift-version`, `TOOLCHAINS`
+  --- environment variable then `swift` in PATH.
+  swift_toolchain = "",
   --- Statusline provider configurations
   statusline = {
     watching = { icon = "", color = "#1abc9c" },
@@ -37,6 +44,8 @@ local defaults = {
     run_picker = "<leader>r", --- set to 0 to disable
     --- Open watch picker. showing run or build, targets, devices and configuration
     watch_picker = "<leader>s", --- set to 0 to disable
+    --- Open test picker. showing test targets, devices and configuration
+    test_picker = "<leader>t", --- set to 0 to disable
     --- A list of all the previous pickers
     all_picker = "<leader>ef", --- set to 0 to disable
     --- horizontal toggle log buffer
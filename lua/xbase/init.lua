@@ -8,6 +8,19 @@ vim.g.xbase = {
   watch = vim.empty_dict(),
   ---@type Device[]
   devices = vim.empty_dict(),
+  ---@type table<string, table<string, TestReport>>
+  tests = vim.empty_dict(),
+}
+
+-- Files and directories defining a project root
+local definitions = {
+  "project.yml",
+  "Project.swift",
+  "Package.swift",
+  "*.xcodeproj",
+  "WORKSPACE",
+  "WORKSPACE.bazel",
+  "MODULE.bazel",
 }
 
 ---Check whether the vim instance should be registered to xbase server.
@@ -15,14 +28,15 @@ vim.g.xbase = {
 ---@param _ table: options to influence the result.
 ---@return boolean
 M.should_register = function(root, _)
-  if vim.loop.fs_stat(root .. "/project.yml") then
-    return true
-  elseif vim.loop.fs_stat(root .. "/Project.swift") then
-    return true
-  elseif vim.loop.fs_stat(root .. "/Package.swift") then
-    return true
-  elseif vim.fn.glob(root .. "/*.xcodeproj"):len() ~= 0 then
-    return true
+  -- Nested packages and projects, e.g. `Packages/*/Package.swift`, are registered too, as deep
+  -- as the daemon searches for them
+  for depth = 0, M.lib.discovery_depth do
+    local dir = root .. string.rep("/*", depth)
+    for _, name in ipairs(definitions) do
+      if vim.fn.glob(dir .. "/" .. name):len() ~= 0 then
+        return true
+      end
+    end
   end
   return false
 end
@@ -38,13 +52,20 @@ end
 
 M.build = function(opts)
   -- I(opts)
+  opts.client = opts.client or require("xbase.util").project_root()
   M.lib.build(opts)
 end
 
 M.run = function(opts)
+  opts.client = opts.client or require("xbase.util").project_root()
   M.lib.run(opts)
 end
 
+M.test = function(opts)
+  opts.client = opts.client or require("xbase.util").project_root()
+  M.lib.test(opts)
+end
+
 ---Tries to register vim instance as client for xbase server.
 ---Only register the vim instance when `xbase.should_attach`
 ---@see xbase.should_attach
@@ -97,6 +118,7 @@ local function bind(config)
     try_map(config.mappings.build_picker, pickers.build)
     try_map(config.mappings.run_picker, pickers.run)
     try_map(config.mappings.watch_picker, pickers.watch)
+    try_map(config.mappings.test_picker, pickers.test)
     try_map(config.mappings.all_picker, pickers.actions)
     try_map(config.mappings.toggle_split_log_buffer, function()
       M.toggle_log_buffer(false)
@@ -20,19 +20,21 @@ local mappings = function(_, _)
       xbase.build(entry)
     elseif entry.command == "Run" then
       xbase.run(entry)
+    elseif entry.command == "Test" then
+      xbase.test(entry)
     end
   end)
 
   return true
 end
 
-local insert_entry = function(acc, picker, command, target, configuration, device)
+local insert_entry = function(acc, picker, command, target_info, configuration, device)
   local item = {
     command = command,
-    settings = { target = target, configuration = configuration },
+    settings = { target = target_info.name, configuration = configuration, scheme = target_info.scheme },
   }
 
-  if command == "Run" then
+  if command == "Run" or command == "Test" then
     item.device = device
   end
 
@@ -51,17 +53,26 @@ end
 
 local get_selections = function(picker)
   local commands = picker == "Watch" and { "Build", "Run" } or { picker }
-  local project = vim.g.xbase.projects[vim.loop.cwd()]
+  local root = util.project_root()
+  local project = vim.g.xbase.projects[root]
 
   if project == nil then
     error "No project info found"
   end
 
-  local targets = util.get_targets_runners(project)
+  local targets
+  if picker == "Test" then
+    targets = util.get_test_targets_runners(project)
+  else
+    targets = util.get_targets_runners(project)
+  end
 
-  -- TOOD(core): Support custom project configurations and schemes
+  -- TOOD(core): Support custom project configurations
   local configurations
-  if vim.loop.fs_stat(vim.loop.cwd() .. "/Package.swift") then
+  local is_bazel = vim.loop.fs_stat(root .. "/WORKSPACE")
+    or vim.loop.fs_stat(root .. "/WORKSPACE.bazel")
+    or vim.loop.fs_stat(root .. "/MODULE.bazel")
+  if vim.loop.fs_stat(root .. "/Package.swift") and not is_bazel then
     configurations = { "Debug" }
   else
     configurations = { "Debug", "Release" }
@@ -71,22 +82,26 @@ local get_selections = function(picker)
 
   for _, command in ipairs(commands) do
     for _, target_info in ipairs(targets) do
-      local target = target_info.name
       local devices = target_info.runners
-      local include_devices = #devices ~= 0 and command == "Run"
+      local include_devices = #devices ~= 0 and (command == "Run" or command == "Test")
+      -- Swift packages and bazel workspaces can only run executables and applications
+      local kind = target_info.kind
+      local is_runnable = kind == nil or kind == "executable" or kind == "application"
 
       for _, configuration in ipairs(configurations) do
-        if include_devices then
+        if command == "Run" and not is_runnable then
+          break
+        elseif include_devices then
           for _, device in ipairs(devices) do
-            insert_entry(results, picker, command, target, configuration, device)
+            insert_entry(results, picker, command, target_info, configuration, device)
           end
         else
-          insert_entry(results, picker, command, target, configuration)
+          insert_entry(results, picker, command, target_info, configuration)
         end
       end
     end
   end
-  if picker == "Run" or picker == "Watch" then
+  if picker == "Run" or picker == "Watch" or picker == "Test" then
     table.sort(results, function(a, b)
       if a.device and b.device then
         return a.device.is_on and not b.device.is_on
@@ -130,6 +145,13 @@ local entry_maker = function(entry)
   entry.ordinal = string.format("%s %s", entry.ordinal, target)
   ti(parts, { target, "TSCharacter" })
 
+  local scheme = entry.settings.scheme
+  if scheme and scheme ~= target then
+    entry.ordinal = string.format("%s %s", entry.ordinal, scheme)
+    ti(items, { width = 20 })
+    ti(parts, { scheme, "TelescopeResultsComment" })
+  end
+
   if device then
     entry.ordinal = string.format("%s %s", entry.ordinal, device)
     ti(items, { width = 30 })
@@ -181,6 +203,16 @@ M.run = function(opts)
   }):find()
 end
 
+M.test = function(opts)
+  opts = themes.get_dropdown(opts or {})
+  picker(opts, {
+    sorter = sorter {},
+    prompt_title = "Test",
+    finder = finder { results = get_selections "Test", entry_maker = entry_maker },
+    attach_mappings = mappings,
+  }):find()
+end
+
 M.actions = function(opts)
   opts = require("telescope.themes").get_dropdown(opts or {})
   picker(opts, {
@@ -191,6 +223,7 @@ M.actions = function(opts)
         { value = "Watch" },
         { value = "Build" },
         { value = "Run" },
+        { value = "Test" },
       },
       entry_maker = function(entry)
         entry.ordinal = entry.value
@@ -222,6 +255,8 @@ M.actions = function(opts)
           M.build(opts)
         elseif selected.value == "Run" then
           M.run(opts)
+        elseif selected.value == "Test" then
+          M.test(opts)
         end
       end)
       return true
@@ -1,12 +1,51 @@
 ---@class Project
 ---@field name string @Project Name or rather xproj generated file name.
 ---@field targets table<string, Target>
+---@field tests string[]? @Project test targets (swift packages and bazel workspaces)
 ---@field root string @Project Root
 ---@field clients number[] @Project connected clients
+---@field toolchain Toolchain? @Swift toolchain (swift packages)
+---@field kinds table<string, SwiftTargetKind|BazelTargetKind>? @Targets kinds (swift packages and bazel workspaces)
+---@field executables table<string, string>? @Executable products names by target name (swift packages)
+---@field schemes table<string, Scheme> @Shared, user, generated or automatically created schemes by name
+---@field target_schemes table<string, string> @Scheme to build each target with, empty when not built with schemes
+---@field shadowed_targets table<string, string[]>? @Other projects defining a target of the same name, not built by name
 ---@field ignorePatterns string[]
 ---@field options ProjectOptions  @Options to override default behaviour
 ---@field packages table<string, ProjectPackage>
 
+---@alias SwiftTargetKind
+---| '"executable"'
+---| '"library"'
+---| '"plugin"'
+---| '"macro"'
+---| '"test"'
+---| '"system"'
+---| '"binary"'
+
+---@alias BazelTargetKind
+---| '"executable"'
+---| '"application"'
+---| '"library"'
+---| '"test"'
+
+---@class Toolchain
+---@field path string @Path to swift binary
+---@field source string @Where the toolchain was resolved from, e.g. `.swift-version (5.7)` or `PATH`
+
+---@class Scheme
+---@field name string @Scheme name
+---@field build_targets string[] @BuildAction targets
+---@field launch SchemeLaunch @LaunchAction
+---@field test_targets string[] @TestAction testables that aren't skipped
+---@field test_configuration string? @TestAction build configuration
+
+---@class SchemeLaunch
+---@field configuration string? @LaunchAction build configuration
+---@field target string? @Target to launch
+---@field args string[] @Enabled launch arguments
+---@field env table<string, string> @Enabled environment variables
+
 ---@class Target
 ---@field type TargetType @Target Type
 ---@field platform Platform[] @Target Platform
@@ -54,6 +93,29 @@
 ---@field state string
 ---@field udid string
 
+---@class BuildDiagnostic
+---@field file string
+---@field line number
+---@field column number
+---@field severity '"error"'|'"warning"'|'"note"'
+---@field message string
+
+---@class TestReport
+---@field target string
+---@field cases TestCase[]
+
+---@class TestCase
+---@field suite string
+---@field name string
+---@field status '"passed"'|'"failed"'|'"skipped"'
+---@field duration number?
+---@field failures TestFailure[]
+
+---@class TestFailure
+---@field file string
+---@field line number
+---@field message string
+
 ---@alias ProjectDependency table<string, string>
 
 ---@alias Platform
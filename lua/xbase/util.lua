@@ -19,6 +19,27 @@ local get_devices = function(platform)
   return devices
 end
 
+---Get root of the nearest registered project enclosing current buffer, otherwise cwd
+---@return string
+M.project_root = function()
+  local path = vim.api.nvim_buf_get_name(0)
+  local root = vim.loop.cwd()
+
+  if path == "" then
+    return root
+  end
+
+  local nearest
+  for project_root, _ in pairs(vim.g.xbase.projects) do
+    local is_enclosing = path == project_root or vim.startswith(path, project_root .. "/")
+    if is_enclosing and (nearest == nil or #project_root > #nearest) then
+      nearest = project_root
+    end
+  end
+
+  return nearest or root
+end
+
 ---Get Targets from project
 ---To Support Multi Platform targets
 ---@param project Project
@@ -28,6 +49,8 @@ M.get_targets_runners = function(project)
   for name, platform in pairs(project.targets) do
     table.insert(targets, {
       name = name,
+      kind = project.kinds and project.kinds[name],
+      scheme = project.target_schemes[name],
       runners = get_devices(platform),
     })
   end
@@ -35,6 +58,31 @@ M.get_targets_runners = function(project)
   return targets
 end
 
+---Get Test Targets from project
+---Swift packages provide test targets separately, otherwise fallback to targets ending with Tests
+---@param project Project
+M.get_test_targets_runners = function(project)
+  local targets = {}
+
+  if project.tests then
+    for _, name in ipairs(project.tests) do
+      table.insert(targets, { name = name, runners = {} })
+    end
+  else
+    for name, platform in pairs(project.targets) do
+      if name:match "Tests$" then
+        table.insert(targets, {
+          name = name,
+          scheme = project.target_schemes[name],
+          runners = get_devices(platform),
+        })
+      end
+    end
+  end
+
+  return targets
+end
+
 M.reload_lsp_servers = function()
   -- local clients = require("lspconfig.util").get_managed_clients()
   -- local ids = ""
@@ -48,8 +96,50 @@ M.reload_lsp_servers = function()
   vim.cmd "LspRestart"
 end
 
+local diagnostics_ns = vim.api.nvim_create_namespace "xbase"
+local severities = {
+  error = vim.diagnostic.severity.ERROR,
+  warning = vim.diagnostic.severity.WARN,
+  note = vim.diagnostic.severity.INFO,
+}
+
+---Replace quickfix list and buffer diagnostics with build diagnostics
+---@param diagnostics BuildDiagnostic[]
+M.set_diagnostics = function(diagnostics)
+  local items, buffers = {}, {}
+
+  vim.diagnostic.reset(diagnostics_ns)
+
+  for _, d in ipairs(diagnostics) do
+    local bufnr = vim.fn.bufadd(d.file)
+    buffers[bufnr] = buffers[bufnr] or {}
+
+    table.insert(buffers[bufnr], {
+      lnum = d.line - 1,
+      col = d.column - 1,
+      severity = severities[d.severity],
+      message = d.message,
+      source = "xbase",
+    })
+
+    table.insert(items, {
+      bufnr = bufnr,
+      lnum = d.line,
+      col = d.column,
+      type = d.severity:sub(1, 1):upper(),
+      text = d.message,
+    })
+  end
+
+  for bufnr, entries in pairs(buffers) do
+    vim.diagnostic.set(diagnostics_ns, bufnr, entries)
+  end
+
+  vim.fn.setqflist({}, "r", { title = "xbase", items = items })
+end
+
 M.is_watching = function(config, command, device)
-  local root = vim.loop.cwd()
+  local root = M.project_root()
   local watching = vim.g.xbase.watcher[root]
 
   local base_key = string.format("-configuration %s", config.configuration)
@@ -75,7 +165,7 @@ M.is_watching = function(config, command, device)
 
   key = key .. " -target " .. config.target
 
-  return watching[key] ~= nil
+  return watching ~= nil and watching[key] ~= nil
 end
 
 M.feline_provider = function()
@@ -10,6 +10,9 @@ pub use types::*;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// Maximum directory depth nested projects are searched for under a registered root
+pub const DISCOVERY_DEPTH: usize = 4;
+
 #[tarpc::service]
 pub trait XBase {
     /// Register project root with a path to setup logs
@@ -18,8 +21,12 @@ pub trait XBase {
     async fn build(req: BuildRequest) -> Result<PathBuf>;
     /// Run Project and get path to where to Runtime log will be located
     async fn run(req: RunRequest) -> Result<PathBuf>;
+    /// Test Project and get path to where to test log will be located
+    async fn test(req: TestRequest) -> Result<PathBuf>;
     /// Drop project root
     async fn drop(req: DropRequest) -> Result<()>;
+    /// Get daemon projects, watchers, running processes and connected clients
+    async fn status() -> Result<Status>;
 }
 
 pub use tarpc::context::{self, Context};
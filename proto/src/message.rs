@@ -2,6 +2,7 @@ use crate::types::*;
 use crate::util::value_or_default;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::path::PathBuf;
 
 #[cfg(feature = "neovim")]
 use mlua::prelude::*;
@@ -50,6 +51,8 @@ pub struct RunRequest {
     pub direction: BufferDirection,
     #[serde(deserialize_with = "value_or_default")]
     pub ops: Operation,
+    #[serde(default)]
+    pub launch: LaunchOptions,
 }
 
 #[cfg(feature = "neovim")]
@@ -62,6 +65,7 @@ impl<'a> FromLua<'a> for RunRequest {
                 direction: table.get("direction")?,
                 device: table.get("device")?,
                 ops: table.get("ops")?,
+                launch: table.get("launch")?,
             })
         } else {
             Err(LuaError::external("Expected a table for BuildRequest"))
@@ -81,10 +85,50 @@ impl Display for RunRequest {
     }
 }
 
+/// Request to Test a particular project target.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TestRequest {
+    pub client: Client,
+    pub settings: BuildSettings,
+    #[serde(deserialize_with = "value_or_default")]
+    pub device: DeviceLookup,
+    #[serde(deserialize_with = "value_or_default")]
+    pub direction: BufferDirection,
+}
+
+#[cfg(feature = "neovim")]
+impl<'a> FromLua<'a> for TestRequest {
+    fn from_lua(value: LuaValue<'a>, _: &'a Lua) -> LuaResult<Self> {
+        if let LuaValue::Table(table) = value {
+            Ok(Self {
+                client: table.get("client")?,
+                settings: table.get("settings")?,
+                device: table.get("device")?,
+                direction: table.get("direction")?,
+            })
+        } else {
+            Err(LuaError::external("Expected a table for TestRequest"))
+        }
+    }
+}
+
+impl Display for TestRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:Test:{}", self.client.root.display(), self.settings)
+    }
+}
+
 /// Request to Register the given client.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RegisterRequest {
     pub client: Client,
+    /// Whether swift packages should be served by xbase build server with a compile database
+    /// generated from `swift build -v` instead of sourcekit-lsp own SwiftPM support.
+    #[serde(default)]
+    pub compile_swift_package: bool,
+    /// Swift binary to build swift packages with, e.g. `<toolchain>/usr/bin/swift`
+    #[serde(default)]
+    pub swift_toolchain: Option<PathBuf>,
 }
 
 #[cfg(feature = "neovim")]
@@ -93,6 +137,12 @@ impl<'a> FromLua<'a> for RegisterRequest {
         if let LuaValue::Table(table) = value {
             Ok(Self {
                 client: table.get("client")?,
+                compile_swift_package: table.get("compile_swift_package").unwrap_or_default(),
+                swift_toolchain: table
+                    .get::<_, Option<String>>("swift_toolchain")
+                    .unwrap_or_default()
+                    .filter(|path| !path.is_empty())
+                    .map(PathBuf::from),
             })
         } else {
             Err(LuaError::external("Expected a table for RegisterRequest"))
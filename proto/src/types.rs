@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::{fmt::Display, path::PathBuf};
+use std::{collections::HashMap, fmt::Display, path::PathBuf};
 use strum::{Display as EnumDisplay, EnumString};
 
 #[cfg(feature = "neovim")]
@@ -125,6 +125,88 @@ impl<'a> FromLua<'a> for BufferDirection {
     }
 }
 
+/// Daemon status snapshot
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Status {
+    /// Registered projects
+    pub projects: Vec<ProjectStatus>,
+    /// Running processes
+    pub runners: Vec<RunnerStatus>,
+    /// Connected client pids
+    pub clients: Vec<i32>,
+}
+
+/// Registered project status
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct ProjectStatus {
+    pub root: PathBuf,
+    pub name: String,
+    pub targets: Vec<String>,
+    /// Pids of clients using the project
+    pub clients: Vec<i32>,
+    /// Keys of active watch listeners
+    pub watchers: Vec<String>,
+}
+
+/// Running process status
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct RunnerStatus {
+    pub key: String,
+    pub root: PathBuf,
+    pub target: String,
+    /// Device name, None when running on the host
+    pub device: Option<String>,
+    /// Process pid, when known
+    pub pid: Option<u32>,
+    /// Seconds since the process was started
+    pub uptime: u64,
+}
+
+/// Event pushed to clients subscribed to the daemon event socket
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClientEvent {
+    /// Pid of the client the event is reported to
+    pub pid: i32,
+    /// Key of the request the event belongs to, if any
+    pub key: Option<String>,
+    #[serde(flatten)]
+    pub kind: ClientEventKind,
+}
+
+/// Client event content
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientEventKind {
+    /// Build, test or runtime log lines
+    Log { lines: Vec<String> },
+    /// Status transition, e.g. running, success, failure, device_running
+    Status { status: String },
+    /// Message to show to the user
+    Message { message: String },
+    /// Error to show to the user
+    Error { message: String },
+}
+
+/// Filter sent by subscribers as first line to the daemon event socket
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct EventSubscription {
+    /// Only receive events reported to a given client pid
+    pub pid: Option<i32>,
+    /// Only receive events of a given request key
+    pub key: Option<String>,
+}
+
+impl EventSubscription {
+    pub fn matches(&self, event: &ClientEvent) -> bool {
+        self.pid.map(|pid| pid == event.pid).unwrap_or(true)
+            && self
+                .key
+                .as_ref()
+                .map(|key| event.key.as_ref() == Some(key))
+                .unwrap_or(true)
+    }
+}
+
 /// Device Lookup information to run built project with
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct DeviceLookup {
@@ -146,6 +228,38 @@ impl<'a> FromLua<'a> for DeviceLookup {
     }
 }
 
+/// Arguments, environment variables and working directory to launch built project with
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct LaunchOptions {
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub cwd: Option<PathBuf>,
+}
+
+impl LaunchOptions {
+    /// Whether no arguments, environment variables or working directory is set
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty() && self.env.is_empty() && self.cwd.is_none()
+    }
+}
+
+#[cfg(feature = "neovim")]
+impl<'a> FromLua<'a> for LaunchOptions {
+    fn from_lua(value: LuaValue<'a>, _: &'a Lua) -> LuaResult<Self> {
+        if let LuaValue::Table(table) = value {
+            Ok(Self {
+                args: table.get("args").unwrap_or_default(),
+                env: table.get("env").unwrap_or_default(),
+                cwd: table.get::<_, Option<String>>("cwd")?.map(PathBuf::from),
+            })
+        } else {
+            Ok(Self::default())
+        }
+    }
+}
+
 impl Default for BufferDirection {
     fn default() -> Self {
         Self::Default
@@ -216,6 +330,20 @@ impl Client {
         })
     }
 
+    /// Create a client that isn't attached to an editor, e.g. command line.
+    pub fn headless<P: Into<PathBuf>>(root: P) -> Self {
+        Self {
+            pid: std::process::id() as i32,
+            root: root.into(),
+            address: Default::default(),
+        }
+    }
+
+    /// Whether the client has no editor instance to connect to.
+    pub fn is_headless(&self) -> bool {
+        self.address.is_empty()
+    }
+
     pub fn abbrev_root(&self) -> String {
         let abbr = || {
             let path = &self.root;
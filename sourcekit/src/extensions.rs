@@ -1,7 +1,4 @@
-use bsp_server::{
-    types::{BuildTargetIdentifier, Url},
-    Message, Notification, RequestId, Response,
-};
+use bsp_server::{types::Url, Message, Notification, RequestId, Response};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -116,7 +113,7 @@ impl TryInto<Message> for OptionsChangedNotification {
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BuildTargetOutputPathsRequest {
-    pub targets: Vec<BuildTargetIdentifier>,
+    pub targets: Vec<TargetIdentifier>,
 }
 
 impl TryInto<BuildTargetOutputPathsRequest> for Value {
@@ -150,17 +147,117 @@ impl BuildTargetOutputPathsResponse {
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BuildTargetOutputPaths {
-    pub target: BuildTargetIdentifier,
+    pub target: TargetIdentifier,
     /// The output paths for sources that belong to this build target.
     pub output_paths: Vec<Url>,
 }
 
 impl BuildTargetOutputPaths {
-    #[allow(dead_code)]
-    pub fn new(target: BuildTargetIdentifier, output_paths: Vec<Url>) -> Self {
+    pub fn new(target: TargetIdentifier, output_paths: Vec<Url>) -> Self {
         Self {
             target,
             output_paths,
         }
     }
 }
+
+/// Build target identifier
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TargetIdentifier {
+    /// The target's uri
+    pub uri: Url,
+}
+
+/// Build target capabilities
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildTargetCapabilities {
+    pub can_compile: bool,
+    pub can_test: bool,
+    pub can_run: bool,
+}
+
+/// Build target contains metadata about an artifact (module) sourcekit-lsp can index.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildTarget {
+    pub id: TargetIdentifier,
+    pub display_name: Option<String>,
+    pub base_directory: Option<Url>,
+    pub tags: Vec<String>,
+    pub language_ids: Vec<String>,
+    pub dependencies: Vec<TargetIdentifier>,
+    pub capabilities: BuildTargetCapabilities,
+}
+
+/// Response containing the list of workspace [`BuildTarget`]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WorkspaceBuildTargetsResponse {
+    pub targets: Vec<BuildTarget>,
+}
+
+impl WorkspaceBuildTargetsResponse {
+    pub fn new(targets: Vec<BuildTarget>) -> Self {
+        Self { targets }
+    }
+
+    pub fn as_response(self, id: RequestId) -> Response {
+        Response::ok(id, self)
+    }
+}
+
+/// Request to query for the list of text documents that belong to build targets.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BuildTargetSourcesRequest {
+    pub targets: Vec<TargetIdentifier>,
+}
+
+impl TryInto<BuildTargetSourcesRequest> for Value {
+    type Error = serde_json::Error;
+
+    fn try_into(self) -> Result<BuildTargetSourcesRequest, Self::Error> {
+        serde_json::from_value(self)
+    }
+}
+
+/// Text document or directory belonging to a build target
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SourceItem {
+    pub uri: Url,
+    /// 1 for files and 2 for directories
+    pub kind: u8,
+    pub generated: bool,
+}
+
+impl SourceItem {
+    pub fn file(uri: Url) -> Self {
+        Self {
+            uri,
+            kind: 1,
+            generated: false,
+        }
+    }
+}
+
+/// Sources of a [`TargetIdentifier`]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SourcesItem {
+    pub target: TargetIdentifier,
+    pub sources: Vec<SourceItem>,
+}
+
+/// Response containing the list of [`SourcesItem`]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BuildTargetSourcesResponse {
+    pub items: Vec<SourcesItem>,
+}
+
+impl BuildTargetSourcesResponse {
+    pub fn new(items: Vec<SourcesItem>) -> Self {
+        Self { items }
+    }
+
+    pub fn as_response(self, id: RequestId) -> Response {
+        Response::ok(id, self)
+    }
+}
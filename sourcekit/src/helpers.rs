@@ -4,6 +4,18 @@ use serde_json::Value;
 use std::path::Path;
 use tap::Pipe;
 
+/// Swift frontend flag preceding the file being compiled
+static PRIMARY_FILE_FLAG: &str = "-primary-file";
+
+/// Clang flags followed by a path derived from the file being compiled
+static CLANG_OUTPUT_FLAGS: [&str; 5] = [
+    "-o",
+    "-MF",
+    "-MT",
+    "--serialize-diagnostics",
+    "-index-unit-output-path",
+];
+
 use std::{fs::read_to_string, path::PathBuf};
 
 /// Try to get indexStorePath from config_filepath or default to "{cache_path}/indexStorePath"
@@ -58,3 +70,134 @@ where
     };
     path().ok_or_else(|| anyhow!("Fail to generate build_cache directory for {root_path:?}"))
 }
+
+/// Find the file closest to `path` with the same extension, searching its directory first then
+/// its ancestors up to `root`, preferring the least nested file.
+pub fn find_sibling_file<'a, I>(files: I, path: &Path, root: &Path) -> Option<PathBuf>
+where
+    I: Iterator<Item = &'a PathBuf> + Clone,
+{
+    let extension = path.extension()?;
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(root))
+        .find_map(|dir| {
+            files
+                .clone()
+                .filter(|file| file.extension() == Some(extension))
+                .filter(|file| file.starts_with(dir))
+                .min_by_key(|file| (file.components().count(), *file))
+        })
+        .cloned()
+}
+
+/// Rewrite `sibling` compile arguments to compile `path` instead.
+///
+/// Swift arguments keep the sibling in the module sources, while clang arguments compile a
+/// single file, so the sibling and its outputs, e.g. object and dependency files, are replaced.
+pub fn infer_compile_args(args: &[String], sibling: &Path, path: &Path) -> Vec<String> {
+    let (sibling_stem, path_stem) = (sibling.file_stem(), path.file_stem());
+    let (sibling, path) = (sibling.display().to_string(), path.display().to_string());
+    let is_swift = sibling.ends_with(".swift");
+
+    let mut inferred = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            let previous = if i > 0 { args[i - 1].as_str() } else { "" };
+            let is_primary = previous == PRIMARY_FILE_FLAG;
+            if arg == &sibling && (!is_swift || is_primary) {
+                path.clone()
+            } else if !is_swift && CLANG_OUTPUT_FLAGS.contains(&previous) {
+                let output = Path::new(arg);
+                match (output.file_stem(), sibling_stem, path_stem) {
+                    (Some(stem), Some(sibling_stem), Some(path_stem)) if stem == sibling_stem => {
+                        let mut name = path_stem.to_os_string();
+                        if let Some(extension) = output.extension() {
+                            name.push(".");
+                            name.push(extension);
+                        }
+                        output.with_file_name(name).display().to_string()
+                    }
+                    _ => arg.clone(),
+                }
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !inferred.contains(&path) {
+        inferred.push(path);
+    }
+
+    inferred
+}
+
+#[test]
+fn test_infer_compile_args() {
+    let files = ["/p/A/a.swift", "/p/A/Views/v.swift", "/p/B/b.m"]
+        .iter()
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    let root = Path::new("/p");
+
+    let sibling = find_sibling_file(files.iter(), Path::new("/p/A/Views/new.swift"), root);
+    assert_eq!(sibling, Some(PathBuf::from("/p/A/Views/v.swift")));
+    let sibling = find_sibling_file(files.iter(), Path::new("/p/A/Models/new.swift"), root);
+    assert_eq!(sibling, Some(PathBuf::from("/p/A/a.swift")));
+    assert_eq!(
+        find_sibling_file(files.iter(), Path::new("/p/B/new.c"), root),
+        None
+    );
+
+    let args = |v: &[&str]| v.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let swift = args(&["-module-name", "A", "/p/A/a.swift"]);
+    assert_eq!(
+        infer_compile_args(&swift, Path::new("/p/A/a.swift"), Path::new("/p/A/n.swift")),
+        args(&["-module-name", "A", "/p/A/a.swift", "/p/A/n.swift"])
+    );
+    let clang = args(&["-x", "objective-c", "-c", "/p/B/b.m"]);
+    assert_eq!(
+        infer_compile_args(&clang, Path::new("/p/B/b.m"), Path::new("/p/B/n.m")),
+        args(&["-x", "objective-c", "-c", "/p/B/n.m"])
+    );
+}
+
+#[test]
+fn test_infer_compile_args_clang_outputs() {
+    let args = |v: &[&str]| v.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let clang = args(&[
+        "-MMD",
+        "-MT",
+        "dependencies",
+        "-MF",
+        "/d/Objects/b.d",
+        "--serialize-diagnostics",
+        "/d/Objects/b.dia",
+        "-c",
+        "/p/B/b.m",
+        "-o",
+        "/d/Objects/b.o",
+        "-index-unit-output-path",
+        "/B.build/Objects/b.o",
+    ]);
+    assert_eq!(
+        infer_compile_args(&clang, Path::new("/p/B/b.m"), Path::new("/p/B/n.m")),
+        args(&[
+            "-MMD",
+            "-MT",
+            "dependencies",
+            "-MF",
+            "/d/Objects/n.d",
+            "--serialize-diagnostics",
+            "/d/Objects/n.dia",
+            "-c",
+            "/p/B/n.m",
+            "-o",
+            "/d/Objects/n.o",
+            "-index-unit-output-path",
+            "/B.build/Objects/n.o",
+        ])
+    );
+}
@@ -1,26 +1,29 @@
-use bsp_server::types::{
-    BuildTargetSources, BuildTargetSourcesResult, InitializeBuild, Url, WorkspaceBuildTargetsResult,
-};
+use bsp_server::types::{BuildTargetSources, InitializeBuild, Url};
 use bsp_server::{Connection, Message, Request, RequestId, Response};
 use log::Level;
 use once_cell::sync::OnceCell;
 use serde_json::{json, Value};
 use std::path::Path;
 use std::sync::Mutex;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use std::{collections::HashMap, path::PathBuf};
 use xclog::{XCCompilationDatabase, XCCompileArgs, XCCompileCommand};
 mod extensions;
 mod helpers;
+mod targets;
 use anyhow::{anyhow, Context, Result};
 use log as tracing; // hack for tracing macros
+use tap::Pipe;
 
 use extensions::*;
 use helpers::*;
+use targets::*;
 
 static SERVER_NAME: &str = "Xbase";
 static SERVER_VERSION: &str = "0.2";
 static STATE: OnceCell<Mutex<State>> = OnceCell::new();
+/// How often `.compile` is checked for changes
+static COMPILE_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 type Conn = Connection;
 type Id = RequestId;
@@ -29,11 +32,99 @@ type Id = RequestId;
 pub struct State {
     compile_db: XCCompilationDatabase,
     file_args: HashMap<PathBuf, XCCompileArgs>,
+    targets: Vec<Target>,
+    /// Files registered for changes and the last compile arguments sent for them
+    registered: HashMap<Url, Vec<String>>,
+    /// Whether compile database was reloaded since registered files were last checked
+    registered_outdated: bool,
     root_path: PathBuf,
     compile_filepath: PathBuf,
     last_modified: SystemTime,
 }
 
+impl State {
+    /// Reload compile database and clear derived data when `.compile` is modified
+    fn reload_if_modified(&mut self) -> Result<()> {
+        let last_modified = std::fs::metadata(&self.compile_filepath)?.modified()?;
+        if self.last_modified == last_modified {
+            return Ok(());
+        }
+
+        log::info!("Reloading compile database ...");
+        self.compile_db = XCCompilationDatabase::try_from_filepath(&self.compile_filepath)?;
+        self.targets = get_targets(&self.compile_db);
+        self.file_args = Default::default();
+        self.last_modified = last_modified;
+        self.registered_outdated = true;
+        Ok(())
+    }
+
+    /// Get compile arguments for a given file, inferring them from a sibling file when the file
+    /// isn't in the compile database yet.
+    fn compile_args(&mut self, path: &Path) -> Result<Vec<String>> {
+        self.reload_if_modified()?;
+
+        if self.file_args.is_empty() {
+            log::debug!("Querying compile_db ...");
+            let file_args = self
+                .compile_db
+                .iter()
+                .flat_map(XCCompileCommand::compile_flags)
+                .flatten()
+                .collect::<HashMap<_, _>>();
+
+            self.file_args.extend(file_args);
+        }
+
+        if let Some(args) = self.file_args.get(path) {
+            log::debug!("Using Cached file args ...");
+            return Ok(args.to_vec());
+        }
+
+        let sibling = find_sibling_file(self.file_args.keys(), path, &self.root_path)
+            .ok_or_else(|| anyhow!("Missing compile arguments for {path:?}"))?;
+        log::info!("Inferring compile arguments for {path:?} from {sibling:?}");
+
+        Ok(infer_compile_args(
+            &self.file_args[&sibling].to_vec(),
+            &sibling,
+            path,
+        ))
+    }
+
+    /// Notifications for registered files whose compile arguments changed since last sent
+    fn changed_options(&mut self) -> Result<Vec<Message>> {
+        self.reload_if_modified()?;
+        if !self.registered_outdated {
+            return Ok(vec![]);
+        }
+        self.registered_outdated = false;
+
+        let uri = Url::from_directory_path(&self.root_path).ok();
+        let files = self.registered.keys().cloned().collect::<Vec<_>>();
+        let mut notifications = vec![];
+
+        for file in files {
+            let args = match self.compile_args(Path::new(file.path())) {
+                Ok(args) => args,
+                Err(err) => {
+                    log::warn!("{err}");
+                    continue;
+                }
+            };
+
+            if self.registered.get(&file) != Some(&args) {
+                log::info!("Options changed: {}", file.path());
+                self.registered.insert(file.clone(), args.clone());
+                notifications
+                    .push(OptionsChangedNotification::new(file, args, uri.clone()).try_into()?);
+            }
+        }
+
+        Ok(notifications)
+    }
+}
+
 fn state() -> &'static Mutex<State> {
     &STATE.get().unwrap()
 }
@@ -47,6 +138,7 @@ fn initialize(params: &InitializeBuild) -> Result<InitializeBuild> {
     let cache_path = get_build_cache_dir(&root_path)?;
     let index_store_path = get_index_store_path(&cache_path, &config_filepath);
     let compile_db = XCCompilationDatabase::try_from_filepath(&compile_filepath)?;
+    let targets = get_targets(&compile_db);
 
     let attr = std::fs::metadata(&compile_filepath)?;
     let last_modified = attr.modified()?;
@@ -68,6 +160,9 @@ fn initialize(params: &InitializeBuild) -> Result<InitializeBuild> {
         .set(Mutex::new(State {
             root_path,
             file_args: Default::default(),
+            targets,
+            registered: Default::default(),
+            registered_outdated: false,
             compile_filepath,
             compile_db,
             last_modified,
@@ -76,44 +171,44 @@ fn initialize(params: &InitializeBuild) -> Result<InitializeBuild> {
     Ok(response)
 }
 
-fn get_compile_args<'a>(path: impl AsRef<Path>) -> Result<XCCompileArgs> {
-    let mut state = state().lock().unwrap();
-    let path = path.as_ref();
+fn get_compile_args<'a>(path: impl AsRef<Path>) -> Result<Vec<String>> {
+    state().lock().unwrap().compile_args(path.as_ref())
+}
 
-    if state.last_modified != std::fs::metadata(&state.compile_filepath)?.modified()? {
-        state.compile_db = XCCompilationDatabase::try_from_filepath(&state.compile_filepath)?;
-        state.file_args = Default::default();
-    }
+/// Poll `.compile` and notify sourcekit-lsp of registered files with changed compile arguments
+fn watch_compile_database(conn: &Conn) {
+    let sender = conn.sender.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(COMPILE_POLL_INTERVAL);
+
+        let notifications = match state().lock().unwrap().changed_options() {
+            Ok(notifications) => notifications,
+            Err(err) => {
+                log::error!("Fail to reload compile database: {err:?}");
+                continue;
+            }
+        };
 
-    if state.file_args.contains_key(path) {
-        log::debug!("Using Cached file args ...");
-        state.file_args.get(path)
-    } else {
-        log::debug!("Querying compile_db ...");
-        let file_args = state
-            .compile_db
-            .iter()
-            .flat_map(XCCompileCommand::compile_flags)
-            .flatten()
-            .collect::<HashMap<_, _>>();
-
-        state.file_args.extend(file_args);
-        state.file_args.get(path)
-    }
-    .map(|r| r.clone())
-    .ok_or_else(|| anyhow!("Missing compile arguments for {path:?}"))
+        for notification in notifications {
+            if sender.send(notification).is_err() {
+                log::info!("Connection closed, stop watching compile database");
+                return;
+            }
+        }
+    });
 }
 
 /// Register or unregister a file options for changes. On change, must send
 /// SourceKitOptionsChanged with list of compiler options to compile the
-/// file.
+/// file. Changes are detected by [`watch_compile_database`].
 #[log::instrument(name = "RegisterForChanges", skip_all)]
 fn register_for_changes(conn: &Conn, id: Id, params: OptionsChangedRequest) -> Result<()> {
     // Empty response, ensure response before notification
     conn.send(Response::ok(id, Value::Null))?;
 
-    if !matches!(params.action, RegisterAction::Register) {
-        log::error!("Unhandled params: {:?}", params);
+    if matches!(params.action, RegisterAction::Unregister) {
+        log::info!("Unregister {}", params.uri.path());
+        state().lock().unwrap().registered.remove(&params.uri);
         return Ok(());
     }
 
@@ -121,7 +216,13 @@ fn register_for_changes(conn: &Conn, id: Id, params: OptionsChangedRequest) -> R
     log::info!("{filepath}");
     let root_path = state().lock().unwrap().root_path.clone();
     let uri = Url::from_directory_path(root_path).ok();
-    let args = get_compile_args(filepath)?.to_vec();
+    let args = get_compile_args(filepath)?;
+
+    state()
+        .lock()
+        .unwrap()
+        .registered
+        .insert(params.uri.clone(), args.clone());
 
     let notification: Message =
         OptionsChangedNotification::new(params.uri, args, uri).try_into()?;
@@ -140,7 +241,7 @@ fn sourcekit_options(conn: &Conn, id: Id, params: OptionsRequest) -> Result<()>
 
     let root_path = state().lock().unwrap().root_path.clone();
     let uri = Url::from_directory_path(root_path).ok();
-    let args = get_compile_args(filepath)?.to_vec();
+    let args = get_compile_args(filepath)?;
     let response = OptionsResponse::new(args, uri).as_response(id);
 
     conn.send(response)?;
@@ -148,13 +249,37 @@ fn sourcekit_options(conn: &Conn, id: Id, params: OptionsRequest) -> Result<()>
     Ok(())
 }
 
+/// Get targets matching given identifiers
+fn get_requested_targets(identifiers: &[TargetIdentifier]) -> Result<Vec<Target>> {
+    let mut state = state().lock().unwrap();
+    state.reload_if_modified()?;
+
+    identifiers
+        .iter()
+        .filter_map(|identifier| target_name(&identifier.uri))
+        .filter_map(|name| state.targets.iter().find(|t| t.name == name))
+        .cloned()
+        .collect::<Vec<_>>()
+        .pipe(Ok)
+}
+
 /// Process Workspace BuildTarget request
 #[log::instrument(name = "WorkspaceBuildTargets", skip_all)]
 fn workspace_build_targets(conn: &Conn, id: Id) -> Result<()> {
     log::debug!("Processing");
-    let response = WorkspaceBuildTargetsResult::new(vec![]);
+    let mut state = state().lock().unwrap();
+    state.reload_if_modified()?;
+
+    let targets = state
+        .targets
+        .iter()
+        .map(|target| target.to_build_target(&state.root_path))
+        .collect::<Vec<_>>();
+    log::info!("{} targets", targets.len());
+
+    let response = WorkspaceBuildTargetsResponse::new(targets).as_response(id);
 
-    conn.send((id, response))?;
+    conn.send(response)?;
 
     Ok(())
 }
@@ -163,7 +288,19 @@ fn workspace_build_targets(conn: &Conn, id: Id) -> Result<()> {
 #[log::instrument(name = "BuildTargetsOutputPaths", skip_all)]
 fn output_paths(conn: &Conn, id: Id, params: BuildTargetOutputPathsRequest) -> Result<()> {
     log::debug!("Processing {params:#?}");
-    let response = BuildTargetOutputPathsResponse::new(vec![]).as_response(id);
+    let items = get_requested_targets(&params.targets)?
+        .into_iter()
+        .map(|target| {
+            let output_paths = target
+                .outputs
+                .iter()
+                .filter_map(|path| Url::from_file_path(path).ok())
+                .collect();
+            BuildTargetOutputPaths::new(TargetIdentifier { uri: target.uri() }, output_paths)
+        })
+        .collect();
+
+    let response = BuildTargetOutputPathsResponse::new(items).as_response(id);
 
     conn.send(response)?;
 
@@ -172,10 +309,26 @@ fn output_paths(conn: &Conn, id: Id, params: BuildTargetOutputPathsRequest) -> R
 
 /// Process BuildTarget Sources Request
 #[log::instrument(name = "BuildTargetsSources", skip_all)]
-fn build_target_sources(conn: &Conn, id: Id, params: BuildTargetSources) -> Result<()> {
+fn build_target_sources(conn: &Conn, id: Id, params: BuildTargetSourcesRequest) -> Result<()> {
     log::debug!("Processing {params:#?}");
-    let response = BuildTargetSourcesResult::new(vec![]);
-    conn.send((id, response))?;
+    let items = get_requested_targets(&params.targets)?
+        .into_iter()
+        .map(|target| {
+            let sources = target
+                .sources
+                .iter()
+                .filter_map(|path| Url::from_file_path(path).ok())
+                .map(SourceItem::file)
+                .collect();
+            SourcesItem {
+                target: TargetIdentifier { uri: target.uri() },
+                sources,
+            }
+        })
+        .collect();
+
+    let response = BuildTargetSourcesResponse::new(items).as_response(id);
+    conn.send(response)?;
     Ok(())
 }
 
@@ -211,7 +364,8 @@ fn handle_message(conn: &Conn, msg: Message) -> Result<()> {
                 }
                 Request::BuildTargetSources(id, value) => {
                     // BuildTargetSources
-                    build_target_sources(conn, id, value)
+                    let params = serde_json::to_value::<BuildTargetSources>(value)?;
+                    build_target_sources(conn, id, params.try_into()?)
                 }
                 Request::Custom(id, method, params) => match method {
                     OptionsChangedRequest::METHOD => {
@@ -252,6 +406,8 @@ fn main() -> Result<()> {
     conn.initialize(|params| initialize(&params).expect("Initialize"))?;
     log::info!("Initialized");
 
+    watch_compile_database(&conn);
+
     for msg in &conn.receiver {
         if let Message::Request(ref req) = msg {
             match handle_shutdown(&conn, req) {
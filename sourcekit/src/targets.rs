@@ -0,0 +1,136 @@
+//! Build targets derived from `.compile` database, grouped by swift/clang module name.
+use crate::extensions::{BuildTarget, BuildTargetCapabilities, TargetIdentifier};
+use bsp_server::types::Url;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use xclog::{XCCompilationDatabase, XCCompileCommand};
+
+/// Scheme used for build target identifier uris
+static TARGET_URI_SCHEME: &str = "xbase";
+
+/// Build target sources and outputs collected from compile commands
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub name: String,
+    pub sources: BTreeSet<PathBuf>,
+    pub outputs: BTreeSet<PathBuf>,
+}
+
+impl Target {
+    /// Build target identifier uri
+    pub fn uri(&self) -> Url {
+        target_uri(&self.name)
+    }
+
+    /// Language ids of target sources
+    pub fn language_ids(&self) -> Vec<String> {
+        self.sources
+            .iter()
+            .filter_map(|path| language_id(path))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// Convert to bsp build target
+    pub fn to_build_target(&self, root: &Path) -> BuildTarget {
+        BuildTarget {
+            id: TargetIdentifier { uri: self.uri() },
+            display_name: Some(self.name.clone()),
+            base_directory: Url::from_directory_path(root).ok(),
+            tags: vec![],
+            language_ids: self.language_ids(),
+            dependencies: vec![],
+            capabilities: BuildTargetCapabilities {
+                can_compile: true,
+                can_test: false,
+                can_run: false,
+            },
+        }
+    }
+}
+
+/// Group compile database files by module name
+pub fn get_targets(compile_db: &XCCompilationDatabase) -> Vec<Target> {
+    let mut targets = BTreeMap::<String, Target>::new();
+
+    for (path, args) in compile_db
+        .iter()
+        .flat_map(XCCompileCommand::compile_flags)
+        .flatten()
+    {
+        let args = args.to_vec();
+        let name = match module_name(&args) {
+            Some(name) => name,
+            None => continue,
+        };
+        let target = targets.entry(name.clone()).or_insert_with(|| Target {
+            name,
+            ..Default::default()
+        });
+
+        if let Some(output) = output_path(&args) {
+            target.outputs.insert(output);
+        }
+        target.sources.insert(path);
+    }
+
+    targets.into_values().collect()
+}
+
+/// Get build target identifier uri for a given target name
+pub fn target_uri(name: &str) -> Url {
+    Url::parse(&format!("{TARGET_URI_SCHEME}:///{name}")).expect("valid target uri")
+}
+
+/// Get target name from build target identifier uri
+pub fn target_name(uri: &Url) -> Option<&str> {
+    if uri.scheme() != TARGET_URI_SCHEME {
+        return None;
+    }
+    uri.path().strip_prefix('/')
+}
+
+/// Module name a file is compiled into, i.e. `-module-name` for swift and `-fmodule-name` for clang
+fn module_name(args: &[String]) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == "-module-name" {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix("-fmodule-name=").map(ToString::to_string)
+        }
+    })
+}
+
+/// Object file a file is compiled into
+fn output_path(args: &[String]) -> Option<PathBuf> {
+    let i = args.iter().position(|arg| arg == "-o")?;
+    args.get(i + 1).map(PathBuf::from)
+}
+
+fn language_id(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "swift" => Some("swift"),
+        "m" => Some("objective-c"),
+        "mm" => Some("objective-cpp"),
+        "c" => Some("c"),
+        "cpp" | "cc" | "cxx" => Some("cpp"),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_module_name() {
+    let args = |v: &[&str]| v.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        module_name(&args(&["-module-name", "Demo", "-Onone"])).as_deref(),
+        Some("Demo")
+    );
+    assert_eq!(
+        module_name(&args(&["-fmodule-name=DemoKit", "-x", "objective-c"])).as_deref(),
+        Some("DemoKit")
+    );
+    assert_eq!(module_name(&args(&["-Onone"])), None);
+    assert_eq!(target_name(&target_uri("Demo")), Some("Demo"));
+}